@@ -0,0 +1,39 @@
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
+    // Rebuild on new commits; a missing path would force a rebuild every time
+    if let Ok(head) = std::fs::read_to_string(".git/HEAD") {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        if let Some(reference) = head.trim().strip_prefix("ref: ") {
+            println!("cargo:rerun-if-changed=.git/{}", reference);
+        }
+    }
+
+    // Short commit hash, if built from a git checkout
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=ALLSCREENSHOTS_GIT_COMMIT={}", commit);
+
+    let target = std::env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
+    println!("cargo:rustc-env=ALLSCREENSHOTS_TARGET={}", target);
+
+    // The resolved SDK version lives in Cargo.lock, not Cargo.toml
+    let sdk_version = std::fs::read_to_string("Cargo.lock")
+        .ok()
+        .and_then(|lock| {
+            let mut lines = lock.lines();
+            lines.find(|line| line.trim() == "name = \"allscreenshots-sdk\"")?;
+            let version = lines.next()?.trim().strip_prefix("version = ")?;
+            Some(version.trim_matches('"').to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=ALLSCREENSHOTS_SDK_VERSION={}", sdk_version);
+}
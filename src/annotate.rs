@@ -0,0 +1,165 @@
+use crate::error::{CliError, CliResult};
+use ab_glyph::{point, Font, FontVec, PxScale, PxScaleFont, ScaleFont};
+use image::{imageops, Rgba, RgbaImage};
+use std::path::Path;
+
+const BAR_COLOR: Rgba<u8> = Rgba([32, 32, 32, 255]);
+const TEXT_COLOR: [u8; 3] = [255, 255, 255];
+
+/// Fonts tried in order when no --font is given
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+];
+
+/// Where the caption bar goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionPosition {
+    Top,
+    Bottom,
+}
+
+impl CaptionPosition {
+    /// Parse an --annotate-position value
+    pub fn parse(s: &str) -> CliResult<Self> {
+        match s.to_lowercase().as_str() {
+            "top" => Ok(CaptionPosition::Top),
+            "bottom" => Ok(CaptionPosition::Bottom),
+            _ => Err(CliError::Other(format!(
+                "Invalid caption position '{}'. Use: top or bottom",
+                s
+            ))),
+        }
+    }
+}
+
+/// Load the caption font from `path`, or the first common system font that exists
+pub fn load_font(path: Option<&Path>) -> CliResult<FontVec> {
+    let path = match path {
+        Some(path) => path,
+        None => SYSTEM_FONTS
+            .iter()
+            .map(Path::new)
+            .find(|path| path.exists())
+            .ok_or_else(|| {
+                CliError::Other(
+                    "No default font found for --annotate. Pass a TrueType or OpenType file with --font"
+                        .to_string(),
+                )
+            })?,
+    };
+
+    let bytes = std::fs::read(path)
+        .map_err(|e| CliError::FileReadError(format!("{}: {}", path.display(), e)))?;
+    FontVec::try_from_vec(bytes)
+        .map_err(|_| CliError::Other(format!("{} isn't a TrueType or OpenType font", path.display())))
+}
+
+/// Fill in the {url}, {device} and {timestamp} placeholders of a caption
+pub fn expand_caption(text: &str, url: &str, device: Option<&str>) -> String {
+    text.replace("{url}", url)
+        .replace("{device}", device.unwrap_or("default"))
+        .replace("{timestamp}", &chrono::Local::now().format("%Y-%m-%d %H:%M").to_string())
+}
+
+/// Add a caption bar above or below the image; long text is cut off with an ellipsis
+pub fn add_caption(image: &RgbaImage, text: &str, font: &FontVec, position: CaptionPosition) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let size = (width as f32 / 60.0).clamp(14.0, 40.0);
+    let bar_height = (size * 1.8).round() as u32;
+    let padding = size * 0.6;
+
+    let mut canvas = RgbaImage::from_pixel(width, height + bar_height, BAR_COLOR);
+    let (image_y, bar_y) = match position {
+        CaptionPosition::Top => (bar_height, 0),
+        CaptionPosition::Bottom => (0, height),
+    };
+    imageops::replace(&mut canvas, image, 0, image_y as i64);
+
+    let scaled = font.as_scaled(PxScale::from(size));
+    let text = fit_text(text, &scaled, width as f32 - padding * 2.0);
+    // Center the line vertically in the bar
+    let baseline = bar_y as f32 + (bar_height as f32 + scaled.ascent() + scaled.descent()) / 2.0;
+
+    let mut x = padding;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = previous {
+            x += scaled.kern(previous, id);
+        }
+        previous = Some(id);
+
+        let glyph = id.with_scale_and_position(size, point(x, baseline));
+        x += scaled.h_advance(id);
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i64 + gx as i64;
+            let py = bounds.min.y as i64 + gy as i64;
+            if px < 0 || py < 0 || px >= width as i64 || py >= canvas.height() as i64 {
+                return;
+            }
+            let pixel = canvas.get_pixel_mut(px as u32, py as u32);
+            for (channel, text) in pixel.0.iter_mut().zip(TEXT_COLOR) {
+                *channel = (*channel as f32 * (1.0 - coverage) + text as f32 * coverage).round() as u8;
+            }
+        });
+    }
+
+    canvas
+}
+
+/// Shorten `text` with an ellipsis until it fits in `max_width` pixels
+fn fit_text(text: &str, font: &PxScaleFont<&FontVec>, max_width: f32) -> String {
+    let width = |s: &str| s.chars().map(|c| font.h_advance(font.glyph_id(c))).sum::<f32>();
+    if width(text) <= max_width {
+        return text.to_string();
+    }
+
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate = format!("{}…", chars.iter().collect::<String>().trim_end());
+        if width(&candidate) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caption_position() {
+        assert_eq!(CaptionPosition::parse("Top").unwrap(), CaptionPosition::Top);
+        assert_eq!(CaptionPosition::parse("bottom").unwrap(), CaptionPosition::Bottom);
+        assert!(CaptionPosition::parse("left").is_err());
+    }
+
+    #[test]
+    fn test_expand_caption() {
+        assert_eq!(
+            expand_caption("{url} on {device}", "https://example.com", Some("iPhone 14")),
+            "https://example.com on iPhone 14"
+        );
+        assert_eq!(expand_caption("Homepage", "https://example.com", None), "Homepage");
+        assert!(!expand_caption("{timestamp}", "", None).contains('{'));
+    }
+
+    #[test]
+    fn test_missing_font() {
+        assert!(load_font(Some(Path::new("/no/such/font.ttf"))).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use crate::error::{CliError, CliResult};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Write files into a zip archive under the given entry names; returns the archive size
+pub fn write_zip(archive: &Path, files: &[(String, PathBuf)]) -> CliResult<u64> {
+    let write_error = |e: &dyn std::fmt::Display| {
+        CliError::FileWriteError(format!("{}: {}", archive.display(), e))
+    };
+
+    if let Some(parent) = archive.parent().filter(|p| !p.as_os_str().is_empty()) {
+        crate::utils::ensure_dir(parent)?;
+    }
+
+    let file = File::create(archive).map_err(|e| write_error(&e))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    // Images are already compressed; deflate still shrinks PNG headers and JSON
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, path) in files {
+        let mut source = File::open(path)
+            .map_err(|e| CliError::FileReadError(format!("{}: {}", path.display(), e)))?;
+        zip.start_file(name.as_str(), options).map_err(|e| write_error(&e))?;
+        // Copy in chunks so large batches never sit in memory
+        io::copy(&mut source, &mut zip).map_err(|e| write_error(&e))?;
+    }
+
+    zip.finish().map_err(|e| write_error(&e))?;
+
+    std::fs::metadata(archive)
+        .map(|m| m.len())
+        .map_err(|e| write_error(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_write_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("001_example.com.png");
+        let manifest = dir.path().join("manifest.json");
+        std::fs::write(&image, b"image bytes").unwrap();
+        std::fs::write(&manifest, b"{}").unwrap();
+
+        let archive = dir.path().join("out/batch.zip");
+        let size = write_zip(
+            &archive,
+            &[
+                ("001_example.com.png".to_string(), image),
+                ("manifest.json".to_string(), manifest),
+            ],
+        )
+        .unwrap();
+        assert_eq!(size, std::fs::metadata(&archive).unwrap().len());
+
+        let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+        assert_eq!(zip.len(), 2);
+        let mut contents = String::new();
+        zip.by_name("001_example.com.png")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "image bytes");
+    }
+
+    #[test]
+    fn test_write_zip_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.png");
+        assert!(write_zip(&dir.path().join("a.zip"), &[("a.png".to_string(), missing)]).is_err());
+    }
+}
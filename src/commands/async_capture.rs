@@ -1,11 +1,16 @@
-use crate::display::{create_spinner, TerminalImage};
+use crate::context::Context;
+use crate::display::{create_spinner, file_hyperlink, hyperlink, show_percent, TerminalImage};
 use crate::error::{CliError, CliResult};
-use crate::utils::{normalize_url, save_to_file};
-use allscreenshots_sdk::{AllscreenshotsClient, ImageFormat, JobStatus, ScreenshotRequest};
+use crate::retry::{
+    is_connect_failure, poll_timeout, reported_progress, retry_async, PollProgress, RetryPolicy,
+    DEFAULT_POLL_TIMEOUT,
+};
+use crate::utils::{expand_device_alias, normalize_url, parse_duration, save_to_file};
+use allscreenshots_sdk::{ImageFormat, JobStatus, ScreenshotRequest};
 use clap::Args;
 use colored::Colorize;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::time::Instant;
 use tokio::time::sleep;
 
 #[derive(Args, Debug)]
@@ -38,9 +43,18 @@ pub struct AsyncArgs {
     #[arg(long)]
     pub no_poll: bool,
 
-    /// Polling interval in seconds
-    #[arg(long, default_value = "2")]
-    pub poll_interval: u64,
+    /// Wait for the job, then print only its hosted result URL instead of downloading it
+    #[arg(long, conflicts_with_all = ["no_poll", "output", "display"])]
+    pub url_only: bool,
+
+    /// Fixed polling interval (e.g., "2s", "500ms"; bare numbers are seconds).
+    /// Without it, polling starts fast and backs off for slow jobs
+    #[arg(long)]
+    pub poll_interval: Option<String>,
+
+    /// Stop waiting after this long in total (e.g., "10m", "2h")
+    #[arg(long, default_value = DEFAULT_POLL_TIMEOUT)]
+    pub poll_timeout: String,
 
     /// Display image in terminal
     #[arg(long)]
@@ -64,15 +78,15 @@ impl AsyncArgs {
     }
 }
 
-pub async fn execute(args: AsyncArgs, api_key: Option<String>) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
+pub async fn execute(args: AsyncArgs, ctx: &Context) -> CliResult<()> {
+    let client = ctx.client()?;
     let url = normalize_url(&args.url)?;
 
     // Build request
     let mut builder = ScreenshotRequest::builder().url(&url);
 
     if let Some(ref device) = args.device {
-        builder = builder.device(device);
+        builder = builder.device(expand_device_alias(device));
     }
 
     let format = match args.format.to_lowercase().as_str() {
@@ -88,18 +102,27 @@ pub async fn execute(args: AsyncArgs, api_key: Option<String>) -> CliResult<()>
         builder = builder.full_page(true);
     }
 
-    let request = builder.build().map_err(|e| CliError::Other(e.to_string()))?;
+    if let Some(css) = ctx.block_css() {
+        builder = builder.custom_css(css);
+    }
 
-    // Create client
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+    let request = builder.build().map_err(|e| CliError::Other(e.to_string()))?;
 
     // Start async job
     let spinner = create_spinner(&format!("Starting async capture for {}...", url));
 
-    let job = client
-        .screenshot_async(&request)
-        .await
-        .map_err(CliError::Sdk)?;
+    // Creating a job isn't idempotent: a timed-out request may still have created one,
+    // so only retry when the connection failed before anything was sent
+    let policy = ctx.retry_policy();
+    let create_policy = RetryPolicy {
+        retry_if: is_connect_failure,
+        ..policy.clone()
+    };
+    let job = retry_async(
+        || async { client.screenshot_async(&request).await.map_err(CliError::Sdk) },
+        &create_policy,
+    )
+    .await?;
 
     spinner.set_message(format!("Job created: {}", job.id));
 
@@ -108,7 +131,7 @@ pub async fn execute(args: AsyncArgs, api_key: Option<String>) -> CliResult<()>
         println!("\n{}", "Job created successfully!".green());
         println!("  Job ID: {}", job.id.cyan());
         if let Some(ref status_url) = job.status_url {
-            println!("  Status URL: {}", status_url.dimmed());
+            println!("  Status URL: {}", hyperlink(status_url, status_url.dimmed()));
         }
         println!("\nUse `allscreenshots jobs get {}` to check status", job.id);
         return Ok(());
@@ -117,16 +140,48 @@ pub async fn execute(args: AsyncArgs, api_key: Option<String>) -> CliResult<()>
     // Poll for completion
     spinner.set_message("Waiting for job to complete...");
 
-    let poll_interval = Duration::from_secs(args.poll_interval);
+    let poll_schedule = ctx.poll_schedule(args.poll_interval.as_deref())?;
+    let timeout = parse_duration(&args.poll_timeout)?;
+    let submitted_at = Instant::now();
+    let mut poll_progress = PollProgress::new(submitted_at);
     let image_bytes = loop {
-        sleep(poll_interval).await;
+        let elapsed = submitted_at.elapsed();
+        sleep(poll_schedule.next_delay(elapsed).min(timeout.saturating_sub(elapsed))).await;
 
-        let status = client.get_job(&job.id).await.map_err(CliError::Sdk)?;
+        // A transient error while polling shouldn't abandon the job
+        let status = retry_async(
+            || async { client.get_job(&job.id).await.map_err(CliError::Sdk) },
+            &policy,
+        )
+        .await?;
+        poll_progress.record();
 
         match status.status {
+            JobStatus::Completed if args.url_only => {
+                spinner.finish_and_clear();
+                let result_url = status.result_url.ok_or_else(|| {
+                    CliError::Other(format!("Job {} completed without a result URL", job.id))
+                })?;
+                if ctx.json {
+                    let result = serde_json::json!({
+                        "id": job.id,
+                        "url": url,
+                        "result_url": result_url,
+                        "expires_at": status.expires_at,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&result).unwrap_or_default());
+                } else {
+                    println!("{}", result_url);
+                }
+                return Ok(());
+            }
             JobStatus::Completed => {
                 spinner.set_message("Downloading result...");
-                let bytes = client.get_job_result(&job.id).await.map_err(CliError::Sdk)?;
+                let bytes = retry_async(
+                    || async { client.get_job_result(&job.id).await.map_err(CliError::Sdk) },
+                    &policy,
+                )
+                .await?;
                 break bytes;
             }
             JobStatus::Failed => {
@@ -141,7 +196,18 @@ pub async fn execute(args: AsyncArgs, api_key: Option<String>) -> CliResult<()>
                 return Err(CliError::Other("Screenshot job was cancelled".to_string()));
             }
             _ => {
-                spinner.set_message(format!("Status: {:?}...", status.status));
+                if let Some(percent) = reported_progress(status.metadata.as_ref()) {
+                    show_percent(&spinner, percent);
+                }
+                spinner.set_message(poll_progress.message(&format!("{:?}", status.status)));
+
+                if submitted_at.elapsed() >= timeout {
+                    spinner.finish_and_clear();
+                    return Err(poll_timeout(
+                        timeout,
+                        format!("Check on it with: allscreenshots jobs get {}", job.id),
+                    ));
+                }
             }
         }
     };
@@ -151,7 +217,7 @@ pub async fn execute(args: AsyncArgs, api_key: Option<String>) -> CliResult<()>
     // Save to file
     if let Some(ref output) = args.output {
         save_to_file(output, &image_bytes)?;
-        println!("{} {}", "Saved to:".green(), output.display());
+        println!("{} {}", "Saved to:".green(), file_hyperlink(output, output.display()));
     }
 
     // Display in terminal
@@ -1,13 +1,24 @@
-use crate::display::{create_progress_bar, create_spinner};
+use crate::archive::write_zip;
+use crate::context::Context;
+use crate::display::{create_rate_progress_bar, create_spinner, icons, RateEta};
 use crate::error::{CliError, CliResult};
-use crate::utils::{batch_output_path, ensure_dir, normalize_url, read_urls_from_file, save_to_file};
-use allscreenshots_sdk::{
-    AllscreenshotsClient, BulkDefaults, BulkRequest, BulkUrlRequest, ImageFormat,
+use crate::manifest::{BatchManifest, ManifestEntry, COMPLETED};
+use crate::rate_limit::RateLimiter;
+use crate::retry::{
+    is_connect_failure, poll_timeout, retry_async, RetryPolicy, DEFAULT_POLL_TIMEOUT,
 };
+use crate::utils::{
+    batch_output_path, diff_images, ensure_dir, expand_device_alias, expand_file_patterns,
+    infer_format_from_path, normalize_url, parse_duration, read_batch_entries,
+    read_urls_from_har, render_output_template, save_to_file, shuffle_urls, suffixed_path,
+    TemplateVars,
+};
+use allscreenshots_sdk::{BulkDefaults, BulkRequest, BulkUrlOptions, BulkUrlRequest};
 use clap::Args;
 use colored::Colorize;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use tokio::time::sleep;
 
 #[derive(Args, Debug)]
@@ -16,18 +27,35 @@ pub struct BatchArgs {
     #[arg(value_name = "URL")]
     pub urls: Vec<String>,
 
-    /// Read URLs from file (one per line)
+    /// Read URLs from file (one per line, or .csv/.json with per-URL output names);
+    /// repeatable, accepts globs like "urls/*.txt"
     #[arg(short, long, value_name = "FILE")]
-    pub file: Option<PathBuf>,
+    pub file: Vec<PathBuf>,
+
+    /// Capture the HTML pages recorded in a HAR file (from browser dev tools); repeatable
+    #[arg(long, value_name = "FILE")]
+    pub har: Vec<PathBuf>,
 
     /// Output directory
-    #[arg(short, long, default_value = "./screenshots")]
+    #[arg(short, long, id = "output", value_name = "DIR", default_value = "./screenshots")]
     pub output_dir: PathBuf,
 
     /// Device preset
     #[arg(short, long)]
     pub device: Option<String>,
 
+    /// Capture every URL once per device (comma-separated); file names get the device
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["device", "retry_failed", "manifest"]
+    )]
+    pub devices: Vec<String>,
+
+    /// With --devices, save each device's screenshots in its own subfolder
+    #[arg(long, requires = "devices")]
+    pub device_folders: bool,
+
     /// Image format
     #[arg(long, default_value = "png")]
     pub format: String,
@@ -40,25 +68,129 @@ pub struct BatchArgs {
     #[arg(long, default_value = "true")]
     pub progress: bool,
 
-    /// Polling interval in seconds
-    #[arg(long, default_value = "2")]
-    pub poll_interval: u64,
+    /// Fixed polling interval (e.g., "2s", "500ms"; bare numbers are seconds).
+    /// Without it, polling starts fast and backs off for slow jobs
+    #[arg(long)]
+    pub poll_interval: Option<String>,
+
+    /// Stop waiting after this long in total (e.g., "10m", "2h")
+    #[arg(long, default_value = DEFAULT_POLL_TIMEOUT)]
+    pub poll_timeout: String,
+
+    /// Download at most N results per second (e.g. 2, or 0.5 for one every 2s)
+    #[arg(long, value_name = "N", value_parser = crate::rate_limit::parse_rate)]
+    pub rate: Option<f64>,
+
+    /// Exit with an error if any URL fails
+    #[arg(long)]
+    pub fail_on_any: bool,
+
+    /// Exit with an error if fewer than this percentage of URLs succeed
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub min_success_rate: Option<u8>,
+
+    /// Only report which URLs rendered; don't download or write any files
+    #[arg(long, alias = "summary-only")]
+    pub no_save: bool,
+
+    /// Randomize URL order before submitting (spreads load across hosts)
+    #[arg(long)]
+    pub shuffle: bool,
+
+    /// Seed for --shuffle, to reproduce a previous order
+    #[arg(long, requires = "shuffle")]
+    pub seed: Option<u64>,
+
+    /// Write per-URL results (status, saved path, error) to a JSON manifest
+    #[arg(long, value_name = "PATH")]
+    pub manifest: Option<PathBuf>,
+
+    /// Re-run the URLs that didn't complete in a previous manifest, into the
+    /// same output directory, and merge the results back into it
+    #[arg(long, value_name = "MANIFEST", conflicts_with_all = ["urls", "file", "har", "manifest", "output", "format"])]
+    pub retry_failed: Option<PathBuf>,
+
+    /// Package the saved screenshots (and manifest) into a zip archive
+    #[arg(long, value_name = "PATH", conflicts_with = "no_save")]
+    pub zip: Option<PathBuf>,
+
+    /// Delete the loose screenshots once they're in the --zip archive
+    #[arg(long, requires = "zip")]
+    pub zip_only: bool,
+
+    /// Compare each capture with the file already in the output directory and
+    /// only overwrite it when the page changed
+    #[arg(long, alias = "only-changed", conflicts_with_all = ["no_save", "shuffle"])]
+    pub only_save_changed: bool,
+
+    /// Percentage of pixels that may differ before a page counts as changed
+    #[arg(long, value_name = "PERCENT", default_value = "0", requires = "only_save_changed")]
+    pub max_diff: f64,
 }
 
-pub async fn execute(args: BatchArgs, api_key: Option<String>) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
+pub async fn execute(args: BatchArgs, ctx: &Context) -> CliResult<()> {
+    let client = ctx.client()?;
+
+    // A retry reuses the previous run's URLs, output directory and format
+    let previous = args.retry_failed.as_deref().map(BatchManifest::load).transpose()?;
+    let output_dir = previous
+        .as_ref()
+        .map_or(&args.output_dir, |m| &m.output_dir)
+        .clone();
+    let format_name = previous.as_ref().map_or(&args.format, |m| &m.format).clone();
 
     // Collect URLs from arguments and/or file
-    let mut urls = args.urls.clone();
+    let mut urls = match previous {
+        Some(ref manifest) => {
+            let failed = manifest.failed_urls();
+            if failed.is_empty() {
+                println!("{} Nothing to retry, every URL completed", icons::success().green());
+                return Ok(());
+            }
+            println!(
+                "  {} Retrying {} of {} URLs",
+                icons::bullet().dimmed(),
+                failed.len(),
+                manifest.entries.len()
+            );
+            failed
+        }
+        None => args.urls.clone(),
+    };
 
-    if let Some(ref file_path) = args.file {
-        let file_urls = read_urls_from_file(file_path)?;
-        urls.extend(file_urls);
+    // File names requested by .csv/.json entries, keyed by normalized URL
+    let mut requested_outputs: HashMap<String, PathBuf> = HashMap::new();
+
+    for file_path in expand_file_patterns(&args.file)? {
+        let entries = read_batch_entries(&file_path)?;
+        println!(
+            "  {} {} URLs from {}",
+            icons::bullet().dimmed(),
+            entries.len(),
+            file_path.display()
+        );
+        for entry in entries {
+            if let Some(output) = entry.output {
+                requested_outputs.entry(normalize_url(&entry.url)?).or_insert(output);
+            }
+            urls.push(entry.url);
+        }
+    }
+
+    for har_path in &args.har {
+        let har_urls = read_urls_from_har(har_path)?;
+        println!(
+            "  {} {} pages from {}",
+            icons::bullet().dimmed(),
+            har_urls.len(),
+            har_path.display()
+        );
+        urls.extend(har_urls);
     }
 
     if urls.is_empty() {
         return Err(CliError::Other(
-            "No URLs provided. Use positional arguments or --file".to_string(),
+            "No URLs provided. Use positional arguments, --file or --har".to_string(),
         ));
     }
 
@@ -68,7 +200,45 @@ pub async fn execute(args: BatchArgs, api_key: Option<String>) -> CliResult<()>
         .map(|u| normalize_url(&u))
         .collect::<Result<Vec<_>, _>>()?;
 
+    // Drop duplicates across sources, keeping the first occurrence
+    let total = urls.len();
+    let mut seen = std::collections::HashSet::new();
+    let urls: Vec<String> = urls.into_iter().filter(|u| seen.insert(u.clone())).collect();
+    if urls.len() < total {
+        println!(
+            "  {} Skipped {} duplicate URLs",
+            icons::bullet().dimmed(),
+            total - urls.len()
+        );
+    }
+
+    // Shuffle with a known seed so the order can be reproduced
+    let urls = if args.shuffle {
+        let seed = args.seed.unwrap_or_else(rand::random);
+        println!("  {} Shuffled with seed {}", icons::bullet().dimmed(), seed);
+        shuffle_urls(urls, seed)
+    } else {
+        urls
+    };
+
+    // One pass per --devices preset, or a single pass with --device
+    let multi_device = !args.devices.is_empty();
+    let devices: Vec<Option<String>> = if multi_device {
+        args.devices.iter().map(|d| Some(d.trim().to_string())).collect()
+    } else {
+        vec![args.device.clone()]
+    };
+
     // Limit check (API limit is 100)
+    let captures = urls.len() * devices.len();
+    if multi_device && captures > 100 {
+        return Err(CliError::Other(format!(
+            "Too many captures ({} URLs x {} devices = {}). Maximum is 100 per batch.",
+            urls.len(),
+            devices.len(),
+            captures
+        )));
+    }
     if urls.len() > 100 {
         return Err(CliError::Other(format!(
             "Too many URLs ({}). Maximum is 100 per batch.",
@@ -76,134 +246,481 @@ pub async fn execute(args: BatchArgs, api_key: Option<String>) -> CliResult<()>
         )));
     }
 
-    println!(
-        "{} {} URLs",
-        "Batch capture:".cyan().bold(),
-        urls.len()
-    );
+    if multi_device {
+        println!(
+            "{} {} URLs x {} devices",
+            "Batch capture:".cyan().bold(),
+            urls.len(),
+            devices.len()
+        );
+    } else {
+        println!(
+            "{} {} URLs",
+            "Batch capture:".cyan().bold(),
+            urls.len()
+        );
+    }
 
     // Ensure output directory exists
-    ensure_dir(&args.output_dir)?;
+    if !args.no_save {
+        ensure_dir(&output_dir)?;
+    }
 
     // Parse format
-    let format = match args.format.to_lowercase().as_str() {
-        "png" => ImageFormat::Png,
-        "jpeg" | "jpg" => ImageFormat::Jpeg,
-        "webp" => ImageFormat::Webp,
-        "pdf" => ImageFormat::Pdf,
-        _ => return Err(CliError::Other(format!("Invalid format: {}", args.format))),
+    let format = match crate::utils::find_format(&format_name) {
+        Some(info) => info.format,
+        None => return Err(CliError::Other(format!("Invalid format: {}", format_name))),
     };
 
-    // Build bulk request with defaults
-    let bulk_urls: Vec<BulkUrlRequest> = urls
-        .iter()
-        .map(|url| BulkUrlRequest::new(url))
-        .collect();
-
-    // Create defaults with device, format, full_page
-    let mut defaults = BulkDefaults::default();
-    defaults.device = args.device.clone();
-    defaults.format = Some(format);
-    if args.full_page {
-        defaults.full_page = Some(true);
-    }
-
-    let bulk_request = BulkRequest::new(bulk_urls).with_defaults(defaults);
-
-    // Create client
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+    // File names from --output-template, checked before the job is created.
+    // With --devices each file gets the device as a subfolder or a name suffix
+    let output_name = |url: &str, index: usize, device: Option<&str>| -> CliResult<PathBuf> {
+        let device_name = device.unwrap_or("default").replace(' ', "_");
+        let dir = if args.device_folders {
+            output_dir.join(&device_name)
+        } else {
+            output_dir.clone()
+        };
+        let path = if let Some(output) = requested_outputs.get(url) {
+            dir.join(output)
+        } else {
+            match ctx.output_template {
+                Some(ref template) => {
+                    let vars = TemplateVars {
+                        url,
+                        device,
+                        format: &format_name,
+                        index: index + 1,
+                    };
+                    dir.join(render_output_template(template, &vars)?)
+                }
+                None => batch_output_path(&dir, url, index, &format_name),
+            }
+        };
+        let named_by_template = !requested_outputs.contains_key(url)
+            && ctx.output_template.as_deref().is_some_and(|t| t.contains("{device}"));
+        if multi_device && !args.device_folders && !named_by_template {
+            Ok(suffixed_path(&path, &device_name))
+        } else {
+            Ok(path)
+        }
+    };
+    output_name(&urls[0], 0, devices[0].as_deref())?;
 
-    // Start bulk job
-    let spinner = create_spinner("Creating batch job...");
-    let bulk_job = client
-        .create_bulk_job(&bulk_request)
-        .await
-        .map_err(CliError::Sdk)?;
-    spinner.finish_and_clear();
+    let policy = ctx.retry_policy();
+    let poll_schedule = ctx.poll_schedule(args.poll_interval.as_deref())?;
+    let timeout = parse_duration(&args.poll_timeout)?;
 
-    println!("  Job ID: {}", bulk_job.id.dimmed());
+    let mut success_count = 0;
+    let mut failed_count = 0;
+    let mut changed_count = 0;
+    let mut new_count = 0;
+    let mut entries = Vec::new();
+    let mut job_ids = Vec::new();
+    // Successes and failures per device, for the summary
+    let mut per_device = Vec::new();
+    let limiter = args.rate.map(RateLimiter::per_second);
+
+    for device in &devices {
+        if multi_device {
+            println!("\n{} {}", "Device:".cyan().bold(), device.as_deref().unwrap_or_default());
+        }
 
-    // Create progress bar
-    let progress = create_progress_bar(urls.len() as u64, "Capturing screenshots");
+        // Build bulk request with defaults
+        // An entry's file extension picks its format, overriding --format
+        let bulk_urls: Vec<BulkUrlRequest> = urls
+            .iter()
+            .map(|url| {
+                match requested_outputs.get(url).and_then(|path| infer_format_from_path(path)) {
+                    Some(format) => BulkUrlRequest::with_options(
+                        url,
+                        BulkUrlOptions {
+                            format: Some(format),
+                            ..Default::default()
+                        },
+                    ),
+                    None => BulkUrlRequest::new(url),
+                }
+            })
+            .collect();
+
+        // Create defaults with device, format, full_page
+        let mut defaults = BulkDefaults {
+            device: device.as_deref().map(|d| expand_device_alias(d).to_string()),
+            format: Some(format),
+            custom_css: ctx.block_css(),
+            ..Default::default()
+        };
+        if args.full_page {
+            defaults.full_page = Some(true);
+        }
 
-    // Poll for completion
-    let poll_interval = Duration::from_secs(args.poll_interval);
-    let final_status = loop {
-        sleep(poll_interval).await;
+        let bulk_request = BulkRequest::new(bulk_urls).with_defaults(defaults);
+
+        // Start bulk job
+        // Only retry the create if it never reached the API, or the batch would run twice
+        let create_policy = RetryPolicy {
+            retry_if: is_connect_failure,
+            ..policy.clone()
+        };
+        let spinner = create_spinner("Creating batch job...");
+        let bulk_job = retry_async(
+            || async { client.create_bulk_job(&bulk_request).await.map_err(CliError::Sdk) },
+            &create_policy,
+        )
+        .await?;
+        spinner.finish_and_clear();
+
+        println!("  Job ID: {}", bulk_job.id.dimmed());
+        job_ids.push(bulk_job.id.clone());
+
+        // Create progress bar; the ETA follows completed URLs rather than poll ticks
+        let eta = RateEta::new();
+        let label = match device {
+            Some(ref device) if multi_device => format!("Capturing {}", device),
+            _ => "Capturing screenshots".to_string(),
+        };
+        let progress = create_rate_progress_bar(urls.len() as u64, &label, &eta);
+
+        // Poll for completion
+        let submitted_at = Instant::now();
+        let final_status = loop {
+            let elapsed = submitted_at.elapsed();
+            sleep(poll_schedule.next_delay(elapsed).min(timeout.saturating_sub(elapsed))).await;
+
+            // A transient error while polling shouldn't abandon the batch
+            let status = retry_async(
+                || async { client.get_bulk_job(&bulk_job.id).await.map_err(CliError::Sdk) },
+                &policy,
+            )
+            .await?;
+
+            eta.observe(status.completed_jobs as u64, Instant::now());
+            progress.set_position(status.completed_jobs as u64);
+
+            // Exit when job is done (any terminal state)
+            match status.status.as_str() {
+                "COMPLETED" | "FAILED" | "PARTIAL" => break status,
+                _ if submitted_at.elapsed() >= timeout => {
+                    progress.abandon();
+                    return Err(poll_timeout(
+                        timeout,
+                        format!(
+                            "Batch job {} keeps rendering; see finished captures with: allscreenshots jobs list",
+                            bulk_job.id
+                        ),
+                    ));
+                }
+                _ => continue,
+            }
+        };
 
-        let status = client
-            .get_bulk_job(&bulk_job.id)
-            .await
-            .map_err(CliError::Sdk)?;
+        progress.finish_with_message("Download complete!");
 
-        progress.set_position(status.completed_jobs as u64);
+        // Download and save results
+        let (succeeded_before, failed_before) = (success_count, failed_count);
+        let first_entry = entries.len();
 
-        // Exit when job is done (any terminal state)
-        match status.status.as_str() {
-            "COMPLETED" | "FAILED" | "PARTIAL" => break status,
-            _ => continue,
+        if args.no_save {
+            println!("\n{}", "Results:".cyan());
+        } else {
+            println!("\n{}", "Saving screenshots...".cyan());
         }
-    };
 
-    progress.finish_with_message("Download complete!");
-
-    // Download and save results
-    let mut success_count = 0;
-    let mut failed_count = 0;
-
-    println!("\n{}", "Saving screenshots...".cyan());
-
-    if let Some(ref jobs) = final_status.jobs {
-        for (i, job) in jobs.iter().enumerate() {
-            if job.status == "COMPLETED" {
-                if job.result_url.is_some() {
-                    // Download from job result endpoint
-                    match client.get_job_result(&job.id).await {
-                        Ok(bytes) => {
-                            let output_path =
-                                batch_output_path(&args.output_dir, &job.url, i, &args.format);
-                            if let Err(e) = save_to_file(&output_path, &bytes) {
-                                eprintln!("  {} Failed to save {}: {}", "✗".red(), job.url, e);
-                                failed_count += 1;
-                            } else {
-                                println!("  {} {}", "✓".green(), output_path.display());
-                                success_count += 1;
-                            }
+        if let Some(ref jobs) = final_status.jobs {
+            for (i, job) in jobs.iter().enumerate() {
+                // Retries keep each URL's original file name prefix
+                let index = previous
+                    .as_ref()
+                    .and_then(|m| m.index_of(&job.url))
+                    .unwrap_or(i);
+
+                // Saved path on success (none with --no-save), error message otherwise
+                let mut changed = None;
+                let outcome = if job.status == "COMPLETED" {
+                    if args.no_save {
+                        println!("  {} {}", icons::success().green(), job.url);
+                        Ok(None)
+                    } else if job.result_url.is_some() {
+                        // Download from job result endpoint
+                        if let Some(ref limiter) = limiter {
+                            limiter.acquire().await;
                         }
-                        Err(e) => {
-                            eprintln!("  {} Failed to download {}: {}", "✗".red(), job.url, e);
-                            failed_count += 1;
+                        let download = retry_async(
+                            || async { client.get_job_result(&job.id).await.map_err(CliError::Sdk) },
+                            &policy,
+                        )
+                        .await;
+                        match download {
+                            Ok(bytes) => {
+                                let saved = output_name(&job.url, index, device.as_deref()).and_then(|path| {
+                                    let change = if args.only_save_changed {
+                                        Some(detect_change(&path, &bytes, args.max_diff))
+                                    } else {
+                                        None
+                                    };
+                                    if !matches!(change, Some(Change::Unchanged)) {
+                                        save_to_file(&path, &bytes)?;
+                                    }
+                                    Ok((path, change))
+                                });
+                                match saved {
+                                    Ok((output_path, None)) => {
+                                        println!("  {} {}", icons::success().green(), output_path.display());
+                                        Ok(Some(output_path))
+                                    }
+                                    Ok((output_path, Some(change))) => {
+                                        report_change(&output_path, &change);
+                                        match change {
+                                            Change::New => new_count += 1,
+                                            Change::Changed(_) => changed_count += 1,
+                                            Change::Unchanged => {}
+                                        }
+                                        changed = Some(!matches!(change, Change::Unchanged));
+                                        Ok(Some(output_path))
+                                    }
+                                    Err(e) => {
+                                        eprintln!("  {} Failed to save {}: {}", icons::failure().red(), job.url, e);
+                                        Err(e.to_string())
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("  {} Failed to download {}: {}", icons::failure().red(), job.url, e);
+                                Err(e.to_string())
+                            }
                         }
+                    } else {
+                        eprintln!("  {} No result URL for {}", icons::failure().red(), job.url);
+                        Err("No result URL".to_string())
                     }
                 } else {
-                    eprintln!("  {} No result URL for {}", "✗".red(), job.url);
-                    failed_count += 1;
-                }
-            } else {
-                let error = job
-                    .error_message
-                    .as_deref()
-                    .unwrap_or("Unknown error");
-                eprintln!("  {} {} - {}", "✗".red(), job.url, error);
-                failed_count += 1;
+                    let error = job
+                        .error_message
+                        .as_deref()
+                        .unwrap_or("Unknown error");
+                    eprintln!("  {} {} - {}", icons::failure().red(), job.url, error);
+                    Err(error.to_string())
+                };
+
+                let entry = match outcome {
+                    Ok(path) => {
+                        success_count += 1;
+                        ManifestEntry {
+                            index,
+                            url: job.url.clone(),
+                            status: COMPLETED.to_string(),
+                            path,
+                            error: None,
+                            changed,
+                        }
+                    }
+                    Err(error) => {
+                        failed_count += 1;
+                        let status = if job.status == "COMPLETED" { "FAILED" } else { job.status.as_str() };
+                        ManifestEntry {
+                            index,
+                            url: job.url.clone(),
+                            status: status.to_string(),
+                            path: None,
+                            error: Some(error),
+                            changed: None,
+                        }
+                    }
+                };
+                entries.push(entry);
+            }
+        }
+
+        // URLs the job never reported on can be retried too
+        for (i, url) in urls.iter().enumerate() {
+            if !entries[first_entry..].iter().any(|e| &e.url == url) {
+                entries.push(ManifestEntry {
+                    index: previous.as_ref().and_then(|m| m.index_of(url)).unwrap_or(i),
+                    url: url.clone(),
+                    status: "MISSING".to_string(),
+                    path: None,
+                    error: Some("No result returned".to_string()),
+                    changed: None,
+                });
             }
         }
+
+        per_device.push((
+            device.clone().unwrap_or_else(|| "default".to_string()),
+            success_count - succeeded_before,
+            failed_count - failed_before,
+        ));
+    }
+
+    // Record results before any exit-code checks so partial runs can be retried
+    let saved: Vec<PathBuf> = entries.iter().filter_map(|e| e.path.clone()).collect();
+    let manifest_path = args.retry_failed.as_deref().or(args.manifest.as_deref());
+    if let Some(path) = manifest_path {
+        let manifest = match previous {
+            Some(mut manifest) => {
+                manifest.merge(&job_ids[0], entries);
+                manifest
+            }
+            None => BatchManifest {
+                job_id: job_ids[0].clone(),
+                output_dir: output_dir.clone(),
+                format: format_name.clone(),
+                entries,
+            },
+        };
+        manifest.save(path)?;
+        println!("  {} Manifest: {}", icons::bullet().dimmed(), path.display());
+    }
+
+    if let Some(ref archive) = args.zip {
+        if saved.is_empty() {
+            eprintln!("{} Nothing saved, skipping {}", "Warning:".yellow(), archive.display());
+        } else {
+            zip_results(archive, &output_dir, &saved, manifest_path, args.zip_only)?;
+        }
     }
 
     // Summary
-    println!("\n{}", "═".repeat(50).dimmed());
+    println!("\n{}", icons::rule(50).dimmed());
     println!("{}", "Batch Summary".bold());
-    println!("  Total: {}", urls.len());
+    println!("  Total: {}", captures);
     println!("  {} {}", "Successful:".green(), success_count);
     if failed_count > 0 {
         println!("  {} {}", "Failed:".red(), failed_count);
     }
-    println!("  Output: {}", args.output_dir.display().to_string().cyan());
-    println!("{}", "═".repeat(50).dimmed());
+    if multi_device {
+        for (device, succeeded, failed) in &per_device {
+            let failed = if *failed > 0 {
+                format!(", {} failed", failed).red().to_string()
+            } else {
+                String::new()
+            };
+            println!("  {} {}: {} of {}{}", icons::bullet().dimmed(), device, succeeded, urls.len(), failed);
+        }
+    }
+    if args.only_save_changed {
+        let compared = success_count - new_count;
+        println!("  {} {} of {} pages changed", "Changed:".yellow(), changed_count, compared);
+        if new_count > 0 {
+            println!("  {} {} (no previous capture)", "New:".cyan(), new_count);
+        }
+    }
+    if let Some(report) = limiter.as_ref().and_then(RateLimiter::report) {
+        println!("  {}", format!("{} to stay under --rate", report).dimmed());
+    }
+    if !args.no_save {
+        println!("  Output: {}", output_dir.display().to_string().cyan());
+    }
+    println!("{}", icons::rule(50).dimmed());
 
     if failed_count > 0 && success_count == 0 {
         return Err(CliError::Other("All screenshots failed".to_string()));
     }
 
+    // Count URLs missing from the job results as failures too
+    if args.fail_on_any && success_count < captures {
+        return Err(CliError::Other(format!(
+            "{} of {} screenshots failed",
+            captures - success_count,
+            captures
+        )));
+    }
+
+    if let Some(min_rate) = args.min_success_rate {
+        let rate = success_count as f64 / captures as f64 * 100.0;
+        if rate < min_rate as f64 {
+            return Err(CliError::Other(format!(
+                "Success rate {:.1}% is below the required {}%",
+                rate, min_rate
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// How a fresh capture compares with the file a previous run left at its path
+enum Change {
+    New,
+    Changed(String),
+    Unchanged,
+}
+
+fn detect_change(path: &Path, bytes: &[u8], max_diff: f64) -> Change {
+    let previous = match std::fs::read(path) {
+        Ok(previous) => previous,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Change::New,
+        Err(e) => return Change::Changed(format!("couldn't read previous file: {}", e)),
+    };
+    match diff_images(&previous, bytes) {
+        Ok(diff) if diff.ratio * 100.0 <= max_diff => Change::Unchanged,
+        Ok(diff) => Change::Changed(diff.describe()),
+        // PDFs and unreadable files can't be compared, so keep the fresh capture
+        Err(e) => Change::Changed(e.to_string()),
+    }
+}
+
+fn report_change(path: &Path, change: &Change) {
+    match change {
+        Change::New => println!("  {} {} {}", icons::success().green(), path.display(), "new".cyan()),
+        Change::Unchanged => {
+            println!("  {} {} {}", icons::success().green(), path.display(), "unchanged".dimmed())
+        }
+        Change::Changed(reason) => println!(
+            "  {} {} {} ({})",
+            icons::warning().yellow(),
+            path.display(),
+            "changed".yellow(),
+            reason
+        ),
+    }
+}
+
+/// Zip the saved screenshots plus the manifest, optionally removing the originals
+fn zip_results(
+    archive: &Path,
+    output_dir: &Path,
+    saved: &[PathBuf],
+    manifest: Option<&Path>,
+    remove_originals: bool,
+) -> CliResult<()> {
+    // Keep paths relative to the output directory so templated subfolders survive
+    let entry_name = |path: &Path| {
+        path.strip_prefix(output_dir)
+            .ok()
+            .or_else(|| path.file_name().map(Path::new))
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+
+    let mut files: Vec<(String, PathBuf)> =
+        saved.iter().map(|path| (entry_name(path), path.clone())).collect();
+    if let Some(path) = manifest {
+        files.push((entry_name(path), path.to_path_buf()));
+    }
+
+    let spinner = create_spinner("Writing archive...");
+    let size = write_zip(archive, &files);
+    spinner.finish_and_clear();
+    let size = size?;
+
+    if remove_originals {
+        for path in saved {
+            if let Err(e) = std::fs::remove_file(path) {
+                eprintln!("{} Failed to remove {}: {}", "Warning:".yellow(), path.display(), e);
+            }
+        }
+    }
+
+    println!(
+        "  {} Archive: {} ({}, {} files)",
+        icons::bullet().dimmed(),
+        archive.display(),
+        crate::utils::format_file_size(size),
+        files.len()
+    );
+
     Ok(())
 }
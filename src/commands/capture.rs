@@ -1,12 +1,29 @@
-use crate::display::{create_spinner, TerminalImage};
+use crate::annotate;
+use crate::context::Context;
+use crate::display::{
+    create_progress_bar, create_spinner, file_hyperlink, icons, Table, TerminalImage,
+};
 use crate::error::{CliError, CliResult};
-use crate::utils::{auto_filename, normalize_url, save_to_file};
-use allscreenshots_sdk::{AllscreenshotsClient, ImageFormat, ScreenshotRequest, WaitUntil, BlockLevel};
+use crate::image_diff::{self, DiffOutput};
+use crate::metadata::{embed_metadata, ScreenshotMetadata, Sidecar};
+use crate::retry::{retry_async, RetryPolicy};
+use crate::utils::{
+    auto_filename, combine_selectors, describe_size, detect_format_from_bytes, diff_images,
+    encode_base64, expand_device_alias, format_extension, hide_css, normalize_url, orient, page_modified_since,
+    parse_since, redact_query, render_output_template,
+    resolve_device, resolve_output_path, save_to_file, selector_slug, suffixed_path, upload,
+    TemplateVars,
+};
+use allscreenshots_sdk::{
+    AllscreenshotsClient, AllscreenshotsError, BlockLevel, ErrorCode, ImageFormat,
+    ScreenshotRequest, WaitUntil,
+};
 use clap::Args;
 use colored::Colorize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Clone)]
 pub struct CaptureArgs {
     /// URL to capture
     #[arg(required = true)]
@@ -20,6 +37,22 @@ pub struct CaptureArgs {
     #[arg(short, long)]
     pub device: Option<String>,
 
+    /// Capture once per device (comma-separated); -o is then a directory
+    #[arg(long, value_delimiter = ',', conflicts_with_all = ["device", "base64", "clipboard"])]
+    pub devices: Vec<String>,
+
+    /// With --devices, stop at the first failed device
+    #[arg(long, requires = "devices")]
+    pub fail_fast: bool,
+
+    /// With --devices, capture devices at the same time and save without previews
+    #[arg(long, requires = "devices")]
+    pub parallel: bool,
+
+    /// Most devices captured at once with --parallel
+    #[arg(long, default_value = "4", requires = "parallel", value_parser = clap::value_parser!(u32).range(1..=16))]
+    pub concurrency: u32,
+
     /// Viewport width in pixels
     #[arg(long)]
     pub width: Option<u32>,
@@ -28,7 +61,15 @@ pub struct CaptureArgs {
     #[arg(long)]
     pub height: Option<u32>,
 
-    /// Image format: png, jpeg, webp, pdf
+    /// Device pixel ratio (1-3); output pixels are the viewport size times this
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=3))]
+    pub scale: Option<u32>,
+
+    /// Orientation: portrait or landscape (swaps the device or viewport size)
+    #[arg(long)]
+    pub orientation: Option<String>,
+
+    /// Image format: png, jpeg, webp, pdf, or auto to keep whatever the API returns
     #[arg(long, default_value = "png")]
     pub format: String,
 
@@ -36,6 +77,16 @@ pub struct CaptureArgs {
     #[arg(long)]
     pub full_page: bool,
 
+    /// Capture the viewport at each scroll offset in CSS pixels (e.g. "0,1000,2000") and
+    /// stitch the shots into one image; a fallback for pages where --full-page breaks
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "PX",
+        conflicts_with_all = ["full_page", "devices", "selector", "selectors"]
+    )]
+    pub scroll_positions: Vec<u32>,
+
     /// Image quality (1-100, for jpeg/webp)
     #[arg(long)]
     pub quality: Option<i32>,
@@ -44,12 +95,16 @@ pub struct CaptureArgs {
     #[arg(long)]
     pub delay: Option<i32>,
 
-    /// CSS selector to wait for before capture
-    #[arg(long)]
-    pub wait_for: Option<String>,
+    /// CSS selector to wait for before capture; repeat to wait for all of them
+    #[arg(long, value_name = "CSS")]
+    pub wait_for: Vec<String>,
 
-    /// Wait until: load, domcontentloaded, networkidle, commit
-    #[arg(long)]
+    /// With several --wait-for selectors, wait for any one instead of all
+    #[arg(long, requires = "wait_for")]
+    pub wait_for_any: bool,
+
+    /// Wait until: load, domcontentloaded, networkidle, commit; a comma list waits for all
+    #[arg(long, value_name = "EVENTS")]
     pub wait_until: Option<String>,
 
     /// Enable dark mode
@@ -72,10 +127,43 @@ pub struct CaptureArgs {
     #[arg(long)]
     pub selector: Option<String>,
 
+    /// Capture each selector (comma-separated) as its own image, e.g. ".header,.footer";
+    /// files get a suffix from the selector
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["selector", "devices", "base64", "clipboard", "compare", "upload_to"]
+    )]
+    pub selectors: Vec<String>,
+
     /// Custom CSS to inject
     #[arg(long)]
     pub custom_css: Option<String>,
 
+    /// Hide elements matching this selector (repeatable); added to defaults.block_selectors
+    #[arg(long, value_name = "SELECTOR")]
+    pub hide: Vec<String>,
+
+    /// Hide scrollbars (on by default with --full-page)
+    #[arg(long, conflicts_with = "show_scrollbars")]
+    pub hide_scrollbars: bool,
+
+    /// Keep scrollbars in --full-page captures
+    #[arg(long)]
+    pub show_scrollbars: bool,
+
+    /// Draw a caption bar on the image; {url}, {device} and {timestamp} are filled in
+    #[arg(long, value_name = "TEXT", conflicts_with_all = ["selectors", "parallel", "repeat"])]
+    pub annotate: Option<String>,
+
+    /// Where the --annotate caption goes: top, bottom
+    #[arg(long, value_name = "POSITION", default_value = "bottom", requires = "annotate")]
+    pub annotate_position: String,
+
+    /// TrueType/OpenType font for --annotate (default: a common system font)
+    #[arg(long, value_name = "PATH", requires = "annotate")]
+    pub font: Option<PathBuf>,
+
     /// Display image in terminal
     #[arg(long)]
     pub display: bool,
@@ -87,6 +175,113 @@ pub struct CaptureArgs {
     /// Copy image to clipboard
     #[arg(long)]
     pub clipboard: bool,
+
+    /// Print the image to stdout as base64 instead of saving or displaying it
+    #[arg(long, conflicts_with_all = ["output", "display", "clipboard"])]
+    pub base64: bool,
+
+    /// With --base64, print a data: URI (e.g. data:image/png;base64,...)
+    #[arg(long, requires = "base64")]
+    pub data_uri: bool,
+
+    /// Embed the URL, capture time, device and CLI version into the saved file
+    #[arg(long)]
+    pub embed_metadata: bool,
+
+    /// Re-encode the image to drop any EXIF or text metadata
+    #[arg(long, conflicts_with = "embed_metadata")]
+    pub strip_metadata: bool,
+
+    /// Print the request JSON sent to the API
+    #[arg(long)]
+    pub print_request: bool,
+
+    /// Exit after printing the request, without capturing
+    #[arg(long, requires = "print_request")]
+    pub dry_run: bool,
+
+    /// Fail if the captured image is narrower than this many pixels
+    #[arg(long, value_name = "PX")]
+    pub min_width: Option<u32>,
+
+    /// Fail if the captured image is shorter than this many pixels
+    #[arg(long, value_name = "PX")]
+    pub min_height: Option<u32>,
+
+    /// Warn if the image is blank or almost a single color
+    #[arg(long)]
+    pub warn_blank: bool,
+
+    /// Compare the capture with a baseline image and exit non-zero if it changed
+    #[arg(long, value_name = "BASELINE", conflicts_with = "devices")]
+    pub compare: Option<PathBuf>,
+
+    /// PUT the image to this presigned HTTPS URL (saved locally too if -o is given)
+    #[arg(long, value_name = "URL", conflicts_with_all = ["devices", "base64"])]
+    pub upload_to: Option<String>,
+
+    /// Write a JSON sidecar with the URL, size, format, device and request options
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["devices", "selectors"])]
+    pub meta: Option<PathBuf>,
+
+    /// Capture the URL this many times back to back, saving numbered files and
+    /// reporting request times and blank results
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(2..=100),
+        conflicts_with_all = [
+            "devices", "selectors", "scroll_positions", "base64", "clipboard", "compare", "upload_to", "meta"
+        ]
+    )]
+    pub repeat: Option<u32>,
+
+    /// With --compare, percentage of pixels allowed to differ
+    #[arg(long, value_name = "PERCENT", default_value = "0", requires = "compare")]
+    pub max_diff: f64,
+
+    /// Also capture this URL with the same options and show where the two differ;
+    /// -o then saves the diff image
+    #[arg(
+        long,
+        value_name = "URL",
+        conflicts_with_all = [
+            "devices", "selectors", "repeat", "scroll_positions", "compare", "base64",
+            "clipboard", "upload_to", "meta", "annotate", "strip_metadata", "embed_metadata",
+            "min_width", "min_height", "warn_blank"
+        ]
+    )]
+    pub compare_against: Option<String>,
+
+    /// With --compare-against, the diff image: side-by-side, overlay, heatmap, changed-only
+    #[arg(long, value_name = "KIND", default_value = "side-by-side", requires = "compare_against")]
+    pub diff_output: String,
+
+    /// Capture the viewport and the full page at once: -o name.png and name.full.png
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "full_page", "devices", "selectors", "repeat", "scroll_positions", "compare",
+            "compare_against", "base64", "clipboard", "upload_to", "meta", "annotate", "embed_metadata"
+        ]
+    )]
+    pub both_viewport_and_full: bool,
+
+    /// With --compare-against, exit non-zero if more than this percentage of pixels differ
+    #[arg(long, value_name = "PERCENT", requires = "compare_against")]
+    pub fail_over: Option<f64>,
+
+    /// Skip the capture if the page hasn't changed since this file was written (or this date)
+    #[arg(long, value_name = "PATH|DATE")]
+    pub if_modified_since: Option<String>,
+
+    /// Crop away a uniform border (the corner color), e.g. padding around an element
+    #[arg(long, conflicts_with_all = ["parallel", "repeat", "compare_against", "both_viewport_and_full"])]
+    pub trim: bool,
+
+    /// With --trim, how far (0-255 per channel) a pixel may be from the border color
+    #[arg(long, value_name = "N", default_value = "10", requires = "trim")]
+    pub trim_tolerance: u8,
 }
 
 impl CaptureArgs {
@@ -95,107 +290,935 @@ impl CaptureArgs {
         if self.no_display {
             return false;
         }
-        self.display || self.output.is_none()
+        self.display || (self.output.is_none() && self.upload_to.is_none())
     }
 }
 
 /// Execute the capture command
-pub async fn execute(args: CaptureArgs, api_key: Option<String>) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
+pub async fn execute(mut args: CaptureArgs, ctx: &Context) -> CliResult<()> {
+    args.hide = ctx.hide_selectors(&args.hide);
+
+    if is_auto_format(&args.format)
+        && (!args.devices.is_empty()
+            || !args.selectors.is_empty()
+            || args.repeat.is_some()
+            || args.compare_against.is_some()
+            || args.both_viewport_and_full)
+    {
+        return Err(CliError::Other(
+            "--format auto only works for a single capture, not with --devices, --selectors, --repeat, \
+             --compare-against or --both-viewport-and-full"
+                .to_string(),
+        ));
+    }
+
+    if let Some(ref baseline) = args.if_modified_since {
+        if !args.dry_run && !page_changed(&args.url, baseline).await? {
+            return Ok(());
+        }
+    }
+
+    if args.compare_against.is_some() {
+        return capture_against(args, ctx).await;
+    }
+
+    if args.both_viewport_and_full {
+        return capture_viewport_and_full(args, ctx).await;
+    }
+
+    if !args.devices.is_empty() {
+        return capture_devices(args, ctx).await;
+    }
+
+    if !args.selectors.is_empty() {
+        return capture_selectors(args, ctx).await;
+    }
+
+    if let Some(times) = args.repeat {
+        return capture_repeated(args, times, ctx).await;
+    }
+
+    capture_one(args, ctx).await
+}
+
+/// Check --if-modified-since; when the server sends no caching headers, assume it changed
+async fn page_changed(url: &str, baseline: &str) -> CliResult<bool> {
+    let url = normalize_url(url)?;
+    let since = modified_baseline(baseline)?;
+
+    match page_modified_since(&url, since).await {
+        Some(true) => Ok(true),
+        Some(false) => {
+            println!(
+                "{}",
+                format!(
+                    "Not modified since {}, skipping capture",
+                    since.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")
+                )
+                .dimmed()
+            );
+            Ok(false)
+        }
+        None => {
+            eprintln!(
+                "{}",
+                "No Last-Modified header from the page; capturing anyway".dimmed()
+            );
+            Ok(true)
+        }
+    }
+}
+
+/// The --if-modified-since baseline: an existing file's modification time, or a date
+fn modified_baseline(value: &str) -> CliResult<chrono::DateTime<chrono::Utc>> {
+    let path = Path::new(value);
+    if path.exists() {
+        let modified = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| CliError::FileReadError(format!("{}: {}", path.display(), e)))?;
+        return Ok(modified.into());
+    }
+    parse_since(value).map_err(|_| {
+        CliError::Other(format!(
+            "--if-modified-since '{}' is neither an existing file nor a date (e.g. 2024-05-01, 12h)",
+            value
+        ))
+    })
+}
+
+/// Capture the same URL once per --devices preset, reporting failures per device
+async fn capture_devices(args: CaptureArgs, ctx: &Context) -> CliResult<()> {
+    if args.parallel {
+        return capture_devices_parallel(args, ctx).await;
+    }
 
     let url = normalize_url(&args.url)?;
+    let template = ctx
+        .output_template
+        .as_deref()
+        .unwrap_or("{domain}_{device}.{format}");
+
+    let mut failed = Vec::new();
+
+    for device in &args.devices {
+        println!("{} {}", "Device:".cyan().bold(), device);
+
+        let mut device_args = args.clone();
+        device_args.devices.clear();
+        device_args.device = Some(device.clone());
+        if let Some(ref dir) = args.output {
+            let vars = TemplateVars {
+                url: &url,
+                device: Some(device),
+                format: &args.format,
+                index: 1,
+            };
+            device_args.output = Some(dir.join(render_output_template(template, &vars)?));
+        }
 
-    // Build the request
-    let mut builder = ScreenshotRequest::builder().url(&url);
+        if let Err(e) = capture_one(device_args, ctx).await {
+            if args.fail_fast {
+                return Err(CliError::Other(format!("{} failed: {}", device, e)));
+            }
+            eprintln!("  {} {} - {}", icons::failure().red(), device, e);
+            failed.push(device.as_str());
+        }
+        println!();
+    }
 
-    if let Some(ref device) = args.device {
-        builder = builder.device(device);
+    if !failed.is_empty() {
+        return Err(CliError::Other(format!(
+            "{} of {} devices failed: {}",
+            failed.len(),
+            args.devices.len(),
+            failed.join(", ")
+        )));
     }
 
-    // Apply custom viewport if width or height specified
-    if args.width.is_some() || args.height.is_some() {
-        use allscreenshots_sdk::ViewportConfig;
-        let mut viewport = ViewportConfig::default();
-        if let Some(width) = args.width {
-            viewport.width = Some(width as i32);
+    Ok(())
+}
+
+/// Capture --devices concurrently behind one progress bar, then list the results
+async fn capture_devices_parallel(args: CaptureArgs, ctx: &Context) -> CliResult<()> {
+    let url = normalize_url(&args.url)?;
+    let template = ctx
+        .output_template
+        .as_deref()
+        .unwrap_or("{domain}_{device}.{format}");
+
+    // Without -o the files land in the current directory
+    let mut requests = Vec::new();
+    let mut paths = Vec::new();
+    let mut presets = Vec::new();
+    for device in &args.devices {
+        let mut device_args = args.clone();
+        device_args.devices.clear();
+        device_args.device = Some(expand_device_alias(device).to_string());
+        let (request, _) = build_request(&device_args, &url)?;
+        presets.extend(device_args.device);
+
+        let vars = TemplateVars {
+            url: &url,
+            device: Some(device),
+            format: &args.format,
+            index: 1,
+        };
+        let name = render_output_template(template, &vars)?;
+        requests.push(request);
+        paths.push(args.output.as_ref().map_or_else(|| PathBuf::from(&name), |dir| dir.join(&name)));
+    }
+
+    if print_requests(&args, &requests.iter().collect::<Vec<_>>())? {
+        return Ok(());
+    }
+
+    let client = ctx.client()?;
+    let progress = create_progress_bar(args.devices.len() as u64, "Capturing devices");
+    let permits = std::sync::Arc::new(tokio::sync::Semaphore::new(args.concurrency as usize));
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for (i, request) in requests.into_iter().enumerate() {
+        let client = client.clone();
+        let permits = permits.clone();
+        let path = paths[i].clone();
+        let policy = ctx.retry_policy();
+        let (task_args, url, preset) = (args.clone(), url.clone(), presets[i].clone());
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result = retry_async(
+                || async { client.screenshot(&request).await.map_err(CliError::Sdk) },
+                &policy,
+            )
+            .await
+            .and_then(|bytes| apply_metadata_flags(bytes, &task_args, &url, Some(&preset)))
+            .and_then(|bytes| save_to_file(&path, &bytes).map(|_| bytes))
+            .and_then(|bytes| {
+                let dims = TerminalImage::get_dimensions(&bytes).ok();
+                check_min_dimensions(dims, task_args.min_width, task_args.min_height).map(|_| bytes)
+            });
+            (i, result)
+        });
+    }
+
+    let mut results: Vec<Option<CliResult<Vec<u8>>>> = args.devices.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        let (i, result) = joined.map_err(|e| CliError::Other(format!("Capture task failed: {}", e)))?;
+        if args.fail_fast {
+            if let Err(ref e) = result {
+                tasks.abort_all();
+                progress.abandon();
+                return Err(CliError::Other(format!("{} failed: {}", args.devices[i], e)));
+            }
         }
-        if let Some(height) = args.height {
-            viewport.height = Some(height as i32);
+        progress.inc(1);
+        results[i] = Some(result);
+    }
+    progress.finish_and_clear();
+
+    let mut table = Table::new(&["", "Device", "Size", "File"]);
+    let mut failed = Vec::new();
+    for ((device, path), result) in args.devices.iter().zip(&paths).zip(results) {
+        match result.unwrap_or_else(|| Err(CliError::Other("No result".to_string()))) {
+            Ok(bytes) => {
+                let size = TerminalImage::get_dimensions(&bytes)
+                    .map(|dims| describe_size(dims, args.scale))
+                    .unwrap_or_default();
+                // Warnings printed while the progress bar runs would break it, so blank shows here
+                let is_blank = args.warn_blank
+                    && crate::utils::content_ratio(&bytes).is_ok_and(|ratio| ratio < BLANK_THRESHOLD);
+                let (icon, file) = if is_blank {
+                    (icons::warning().yellow().to_string(), format!("{} {}", path.display(), "blank".yellow()))
+                } else {
+                    (icons::success().green().to_string(), path.display().to_string())
+                };
+                table.add_row(vec![icon, device.clone(), size, file]);
+            }
+            Err(e) => {
+                table.add_row(vec![
+                    icons::failure().red().to_string(),
+                    device.clone(),
+                    String::new(),
+                    e.to_string().red().to_string(),
+                ]);
+                failed.push(device.as_str());
+            }
         }
-        builder = builder.viewport(viewport);
+    }
+    table.print();
+
+    if !failed.is_empty() {
+        return Err(CliError::Other(format!(
+            "{} of {} devices failed: {}",
+            failed.len(),
+            args.devices.len(),
+            failed.join(", ")
+        )));
     }
 
-    let format = parse_format(&args.format)?;
-    builder = builder.format(format.clone());
+    Ok(())
+}
 
-    if args.full_page {
-        builder = builder.full_page(true);
+/// Run two capture requests at the same time
+async fn capture_pair(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    first: &ScreenshotRequest,
+    second: &ScreenshotRequest,
+) -> CliResult<(Vec<u8>, Vec<u8>)> {
+    let (first, second) = tokio::join!(
+        retry_async(|| async { client.screenshot(first).await.map_err(CliError::Sdk) }, policy),
+        retry_async(|| async { client.screenshot(second).await.map_err(CliError::Sdk) }, policy),
+    );
+    Ok((first?, second?))
+}
+
+/// Capture the viewport and the full page together, saving `<name>.<ext>` and `<name>.full.<ext>`
+async fn capture_viewport_and_full(mut args: CaptureArgs, ctx: &Context) -> CliResult<()> {
+    let url = normalize_url(&args.url)?;
+    expand_device(&mut args, ctx.verbose);
+
+    let (viewport_request, format) = build_request(&args, &url)?;
+    let full_args = CaptureArgs {
+        full_page: true,
+        ..args.clone()
+    };
+    let (full_request, _) = build_request(&full_args, &url)?;
+    if format == ImageFormat::Pdf {
+        return Err(CliError::Other(
+            "--both-viewport-and-full needs an image format, not pdf".to_string(),
+        ));
+    }
+    if print_requests(&args, &[&viewport_request, &full_request])? {
+        return Ok(());
     }
 
-    if let Some(quality) = args.quality {
-        builder = builder.quality(quality);
+    let vars = TemplateVars {
+        url: &url,
+        device: args.device.as_deref(),
+        format: &args.format,
+        index: 1,
+    };
+    let viewport_path = match args.output {
+        Some(ref output) => resolve_output_path(output, ctx.output_template.as_deref(), &vars)?,
+        None => PathBuf::from(auto_filename(&url, &args.format)),
+    };
+    let full_path = full_page_path(&viewport_path);
+
+    let client = ctx.client()?;
+    let spinner = create_spinner(&format!("Capturing viewport and full page of {}...", url));
+    let (viewport, full) = capture_pair(&client, &ctx.retry_policy(), &viewport_request, &full_request).await?;
+    spinner.finish_and_clear();
+
+    let (viewport, full) = if args.strip_metadata {
+        (strip_metadata(viewport, args.quality)?, strip_metadata(full, args.quality)?)
+    } else {
+        (viewport, full)
+    };
+
+    println!("{}", "Screenshots captured!".green().bold());
+    for (label, path, bytes) in [("Viewport", &viewport_path, &viewport), ("Full page", &full_path, &full)] {
+        save_to_file(path, bytes)?;
+        let size = match TerminalImage::get_dimensions(bytes) {
+            Ok((width, height)) => format!("{}x{}, ", width, height),
+            Err(_) => String::new(),
+        };
+        println!(
+            "  {}: {} ({}{})",
+            label,
+            file_hyperlink(path, path.display().to_string().cyan()),
+            size,
+            crate::utils::format_file_size(bytes.len() as u64)
+        );
     }
 
-    if let Some(delay) = args.delay {
-        builder = builder.delay(delay);
+    // Only the viewport shot is shown, a full page preview is mostly scrollbar
+    if args.should_display() {
+        println!();
+        let display = TerminalImage::new();
+        display.display_bytes(&viewport)?;
+        println!();
     }
 
-    if let Some(ref wait_for) = args.wait_for {
-        builder = builder.wait_for(wait_for);
+    // --warn-blank and --min-width/--min-height hold for both shots
+    check_capture(&viewport, &args)?;
+    check_capture(&full, &args)
+}
+
+/// "shots/page.png" -> "shots/page.full.png"
+fn full_page_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{}.full.{}", stem, ext.to_string_lossy()),
+        None => format!("{}.full", stem),
+    };
+    path.with_file_name(name)
+}
+
+/// Capture two URLs with the same options and diff them, for a quick A/B of two environments
+async fn capture_against(mut args: CaptureArgs, ctx: &Context) -> CliResult<()> {
+    let kind = DiffOutput::parse(&args.diff_output)?;
+    let url_a = normalize_url(&args.url)?;
+    let url_b = normalize_url(args.compare_against.as_deref().unwrap_or_default())?;
+    expand_device(&mut args, ctx.verbose);
+
+    let (request_a, format) = build_request(&args, &url_a)?;
+    let (request_b, _) = build_request(&args, &url_b)?;
+    if format == ImageFormat::Pdf {
+        return Err(CliError::Other("--compare-against needs an image format, not pdf".to_string()));
+    }
+    if print_requests(&args, &[&request_a, &request_b])? {
+        return Ok(());
     }
 
-    if let Some(ref wait_until) = args.wait_until {
-        let wait = parse_wait_until(wait_until)?;
-        builder = builder.wait_until(wait);
+    let client = ctx.client()?;
+    let spinner = create_spinner(&format!("Capturing {} and {}...", url_a, url_b));
+    let (bytes_a, bytes_b) = capture_pair(&client, &ctx.retry_policy(), &request_a, &request_b).await?;
+
+    spinner.set_message("Comparing...");
+    let images = crate::local_compose::decode_bytes(&[bytes_a, bytes_b])?;
+    let (image_a, image_b) = (images[0].to_rgba8(), images[1].to_rgba8());
+    let diff = image_diff::render(&image_a, &image_b, kind);
+    let (changed, total) = (diff.changed, diff.total);
+    let percent = diff.changed_ratio() * 100.0;
+    let diff_bytes = crate::utils::encode_image(
+        &image::DynamicImage::ImageRgba8(diff.image),
+        image::ImageFormat::Png,
+        None,
+    )?;
+    spinner.finish_and_clear();
+
+    println!("{} {}", "A:".cyan().bold(), url_a);
+    println!("{} {}", "B:".cyan().bold(), url_b);
+    if let Some(ref device) = args.device {
+        println!("  Device: {}", device);
+    }
+    println!(
+        "  Similarity: {} ({} of {} pixels differ)",
+        format!("{:.2}%", 100.0 - percent).bold(),
+        changed,
+        total
+    );
+    if image_a.dimensions() != image_b.dimensions() {
+        println!(
+            "  {}",
+            format!(
+                "Sizes differ ({}x{} vs {}x{}); aligned at the top-left corner",
+                image_a.width(),
+                image_a.height(),
+                image_b.width(),
+                image_b.height()
+            )
+            .dimmed()
+        );
     }
 
-    if args.dark_mode {
-        builder = builder.dark_mode(true);
+    if let Some(ref output) = args.output {
+        save_to_file(output, &diff_bytes)?;
+        println!(
+            "  Diff saved to: {}",
+            file_hyperlink(output, output.display().to_string().cyan())
+        );
     }
 
-    if args.block_ads {
-        builder = builder.block_ads(true);
+    if args.should_display() && changed > 0 {
+        println!();
+        let display = TerminalImage::new();
+        display.display_bytes(&diff_bytes)?;
+        println!();
     }
 
-    if args.block_cookies {
-        builder = builder.block_cookie_banners(true);
+    match args.fail_over {
+        Some(limit) if percent > limit => Err(CliError::Other(format!(
+            "{:.2}% of pixels differ between the two URLs (allowed {}%)",
+            percent, limit
+        ))),
+        _ => Ok(()),
     }
+}
 
-    if let Some(ref level) = args.block_level {
-        let block_level = parse_block_level(level)?;
-        builder = builder.block_level(block_level);
+/// Capture the same URL `times` times in a row and compare the attempts
+async fn capture_repeated(mut args: CaptureArgs, times: u32, ctx: &Context) -> CliResult<()> {
+    let url = normalize_url(&args.url)?;
+    expand_device(&mut args, ctx.verbose);
+    let (request, _) = build_request(&args, &url)?;
+
+    // Attempts are numbered after the -o file (or an automatic name): page_01.png, ...
+    let vars = TemplateVars {
+        url: &url,
+        device: args.device.as_deref(),
+        format: &args.format,
+        index: 1,
+    };
+    let base = match args.output {
+        Some(ref output) => resolve_output_path(output, ctx.output_template.as_deref(), &vars)?,
+        None => PathBuf::from(auto_filename(&url, &args.format)),
+    };
+    let digits = times.to_string().len();
+
+    if print_requests(&args, &[&request])? {
+        return Ok(());
     }
 
-    if let Some(ref selector) = args.selector {
-        builder = builder.selector(selector);
+    let client = ctx.client()?;
+    let progress = create_progress_bar(times as u64, &format!("Capturing {}", url));
+
+    let mut table = Table::new(&["", "#", "Time", "Size", "File"]).align_right(1).align_right(2);
+    let mut times_taken = Vec::new();
+    let mut blank = Vec::new();
+    let mut failed = Vec::new();
+
+    for attempt in 1..=times {
+        // No retries: a failed attempt is exactly what --repeat is meant to surface
+        let started = std::time::Instant::now();
+        let result = client.screenshot(&request).await.map_err(CliError::Sdk);
+        let elapsed = started.elapsed();
+        progress.inc(1);
+
+        let failure_row = |e: CliError, size: String| {
+            vec![
+                icons::failure().red().to_string(),
+                attempt.to_string(),
+                format_seconds(elapsed),
+                size,
+                e.to_string().red().to_string(),
+            ]
+        };
+        let bytes = match result.and_then(|bytes| apply_metadata_flags(bytes, &args, &url, args.device.as_deref())) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                table.add_row(failure_row(e, String::new()));
+                failed.push(attempt);
+                continue;
+            }
+        };
+        times_taken.push(elapsed);
+
+        let path = suffixed_path(&base, &format!("{:0width$}", attempt, width = digits));
+        save_to_file(&path, &bytes)?;
+
+        let dims = TerminalImage::get_dimensions(&bytes).ok();
+        if let Err(e) = check_min_dimensions(dims, args.min_width, args.min_height) {
+            let size = dims.map(|dims| describe_size(dims, args.scale)).unwrap_or_default();
+            table.add_row(failure_row(e, size));
+            failed.push(attempt);
+            continue;
+        }
+
+        let is_blank = crate::utils::content_ratio(&bytes).is_ok_and(|ratio| ratio < BLANK_THRESHOLD);
+        let icon = if is_blank {
+            blank.push(attempt);
+            icons::warning().yellow().to_string()
+        } else {
+            icons::success().green().to_string()
+        };
+        let size = TerminalImage::get_dimensions(&bytes)
+            .map(|dims| describe_size(dims, args.scale))
+            .unwrap_or_default();
+        let file = if is_blank {
+            format!("{} {}", path.display(), "blank".yellow())
+        } else {
+            path.display().to_string()
+        };
+        table.add_row(vec![icon, attempt.to_string(), format_seconds(elapsed), size, file]);
+    }
+    progress.finish_and_clear();
+
+    table.print();
+    println!();
+
+    if let (Some(min), Some(max)) = (times_taken.iter().min(), times_taken.iter().max()) {
+        let average = times_taken.iter().sum::<Duration>() / times_taken.len() as u32;
+        println!(
+            "  Request time: min {}, avg {}, max {}",
+            format_seconds(*min),
+            format_seconds(average),
+            format_seconds(*max)
+        );
+    }
+    let list = |attempts: &[u32]| attempts.iter().map(u32::to_string).collect::<Vec<_>>().join(", ");
+    if !blank.is_empty() {
+        println!("  {} Blank: attempt {}", icons::warning().yellow(), list(&blank));
+    }
+    if !failed.is_empty() {
+        return Err(CliError::Other(format!(
+            "{} of {} captures failed (attempt {})",
+            failed.len(),
+            times,
+            list(&failed)
+        )));
     }
 
-    if let Some(ref css) = args.custom_css {
-        builder = builder.custom_css(css);
+    Ok(())
+}
+
+fn format_seconds(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
+
+/// Capture every --selectors element concurrently, one request each
+async fn capture_selectors(mut args: CaptureArgs, ctx: &Context) -> CliResult<()> {
+    let url = normalize_url(&args.url)?;
+    expand_device(&mut args, ctx.verbose);
+
+    // Element files are named after the -o file (or an automatic name) plus the selector
+    let vars = TemplateVars {
+        url: &url,
+        device: args.device.as_deref(),
+        format: &args.format,
+        index: 1,
+    };
+    let base = match args.output {
+        Some(ref output) => resolve_output_path(output, ctx.output_template.as_deref(), &vars)?,
+        None => PathBuf::from(auto_filename(&url, &args.format)),
+    };
+
+    let selectors = std::mem::take(&mut args.selectors);
+    let mut requests = Vec::new();
+    let mut format = ImageFormat::Png;
+    for selector in &selectors {
+        let mut selector_args = args.clone();
+        selector_args.selector = Some(selector.clone());
+        let (request, request_format) = build_request(&selector_args, &url)?;
+        requests.push(request);
+        format = request_format;
+    }
+    if print_requests(&args, &requests.iter().collect::<Vec<_>>())? {
+        return Ok(());
+    }
+    if args.trim && format == ImageFormat::Pdf {
+        eprintln!("{} --trim is skipped for PDFs", "Warning:".yellow());
     }
 
-    let request = builder.build().map_err(|e| CliError::Other(e.to_string()))?;
+    let client = ctx.client()?;
+    let spinner = create_spinner(&format!("Capturing {} elements of {}...", selectors.len(), url));
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for (i, request) in requests.into_iter().enumerate() {
+        let client = client.clone();
+        let policy = ctx.retry_policy();
+        tasks.spawn(async move {
+            let result = retry_async(
+                || async { client.screenshot(&request).await.map_err(CliError::Sdk) },
+                &policy,
+            )
+            .await;
+            (i, result)
+        });
+    }
+
+    let mut results: Vec<Option<CliResult<Vec<u8>>>> = selectors.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        let (i, result) = joined.map_err(|e| CliError::Other(format!("Capture task failed: {}", e)))?;
+        results[i] = Some(result);
+    }
+    spinner.finish_and_clear();
+
+    let mut unmatched = Vec::new();
+    let mut failed = 0;
+
+    for (selector, result) in selectors.iter().zip(results) {
+        match result.unwrap_or_else(|| Err(CliError::Other("No result".to_string()))) {
+            Ok(mut bytes) => {
+                let trimmed_from = if args.trim && format != ImageFormat::Pdf {
+                    trim_capture(&mut bytes, args.trim_tolerance, args.quality)?
+                } else {
+                    None
+                };
+                let bytes = apply_metadata_flags(bytes, &args, &url, args.device.as_deref())?;
+                let path = suffixed_path(&base, &selector_slug(selector));
+                save_to_file(&path, &bytes)?;
+                if let Err(e) = check_capture(&bytes, &args) {
+                    eprintln!("  {} {} -> {} - {}", icons::failure().red(), selector, path.display(), e);
+                    failed += 1;
+                    continue;
+                }
+                let size = TerminalImage::get_dimensions(&bytes)
+                    .map(|dims| match trimmed_from {
+                        Some((width, height)) => {
+                            format!(" ({}, trimmed from {}x{})", describe_size(dims, args.scale), width, height)
+                        }
+                        None => format!(" ({})", describe_size(dims, args.scale)),
+                    })
+                    .unwrap_or_default();
+                println!("  {} {} -> {}{}", icons::success().green(), selector, path.display(), size);
+            }
+            // The API answers 404 when the selector matched no element
+            Err(CliError::Sdk(AllscreenshotsError::ApiError {
+                code: ErrorCode::NotFound,
+                ..
+            })) => {
+                eprintln!("  {} {} matched nothing", icons::warning().yellow(), selector);
+                unmatched.push(selector.as_str());
+            }
+            Err(e) => {
+                eprintln!("  {} {} - {}", icons::failure().red(), selector, e);
+                failed += 1;
+            }
+        }
+    }
+
+    let saved = selectors.len() - unmatched.len() - failed;
+    println!();
+    println!("{} {} of {} elements saved", "Done:".green().bold(), saved, selectors.len());
+    if !unmatched.is_empty() {
+        println!("  No match: {}", unmatched.join(", ").yellow());
+    }
+
+    if saved == 0 {
+        return Err(CliError::Other("No elements were captured".to_string()));
+    }
+    if failed > 0 {
+        return Err(CliError::Other(format!("{} of {} element captures failed", failed, selectors.len())));
+    }
+
+    Ok(())
+}
+
+/// Print the requests for --print-request (an array if there are several);
+/// true when --dry-run says to stop before sending them
+fn print_requests(args: &CaptureArgs, requests: &[&ScreenshotRequest]) -> CliResult<bool> {
+    if !args.print_request {
+        return Ok(false);
+    }
+
+    let json = match requests {
+        [request] => serde_json::to_string_pretty(request),
+        requests => serde_json::to_string_pretty(requests),
+    }
+    .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
+    println!("{}", json);
+
+    Ok(args.dry_run)
+}
+
+/// --strip-metadata and --embed-metadata for bytes about to be saved or sent
+fn apply_metadata_flags(
+    image_bytes: Vec<u8>,
+    args: &CaptureArgs,
+    url: &str,
+    device: Option<&str>,
+) -> CliResult<Vec<u8>> {
+    if args.strip_metadata {
+        return strip_metadata(image_bytes, args.quality);
+    }
+    if !args.embed_metadata {
+        return Ok(image_bytes);
+    }
+
+    match embed_metadata(&image_bytes, &ScreenshotMetadata::new(url, device))? {
+        Some(bytes) => Ok(bytes),
+        None => {
+            eprintln!(
+                "{} {} files can't carry metadata, saving without it",
+                "Warning:".yellow(),
+                args.format
+            );
+            Ok(image_bytes)
+        }
+    }
+}
+
+/// --warn-blank and --min-width/--min-height for modes that save several files
+fn check_capture(image_bytes: &[u8], args: &CaptureArgs) -> CliResult<()> {
+    if args.warn_blank {
+        warn_if_blank(image_bytes);
+    }
+    check_min_dimensions(
+        TerminalImage::get_dimensions(image_bytes).ok(),
+        args.min_width,
+        args.min_height,
+    )
+}
+
+/// Capture a single screenshot with the given options
+async fn capture_one(mut args: CaptureArgs, ctx: &Context) -> CliResult<()> {
+    let url = normalize_url(&args.url)?;
+
+    expand_device(&mut args, ctx.verbose);
+
+    let (request, format) = build_request(&args, &url)?;
+
+    if print_requests(&args, &[&request])? {
+        return Ok(());
+    }
+
+    // Resolve the file name now so a bad template fails before using a capture
+    let output = args
+        .output
+        .as_deref()
+        .map(|output| {
+            let vars = TemplateVars {
+                url: &url,
+                device: args.device.as_deref(),
+                format: &args.format,
+                index: 1,
+            };
+            resolve_output_path(output, ctx.output_template.as_deref(), &vars)
+        })
+        .transpose()?;
+
+    if let Some(ref destination) = args.upload_to {
+        check_upload_destination(destination)?;
+    }
+
+    // Read the baseline up front so a bad path doesn't cost a capture
+    let baseline = match args.compare {
+        Some(ref path) => {
+            if format == ImageFormat::Pdf {
+                return Err(CliError::Other("--compare needs an image format, not pdf".to_string()));
+            }
+            let bytes = std::fs::read(path)
+                .map_err(|e| CliError::FileReadError(format!("{}: {}", path.display(), e)))?;
+            Some(bytes)
+        }
+        None => None,
+    };
+
+    // Load the caption font up front so a bad --font fails before the capture
+    let caption = match args.annotate {
+        Some(_) if format == ImageFormat::Pdf => {
+            eprintln!("{} --annotate is skipped for PDFs", "Warning:".yellow());
+            None
+        }
+        Some(ref text) => Some((
+            annotate::expand_caption(text, &url, args.device.as_deref()),
+            annotate::CaptionPosition::parse(&args.annotate_position)?,
+            annotate::load_font(args.font.as_deref())?,
+        )),
+        None => None,
+    };
 
     // Create client and capture
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+    let client = ctx.client()?;
 
     let spinner = create_spinner(&format!("Capturing {}...", url));
 
-    let image_bytes = client.screenshot(&request).await.map_err(CliError::Sdk)?;
+    let image_bytes = if args.scroll_positions.is_empty() {
+        retry_async(
+            || async { client.screenshot(&request).await.map_err(CliError::Sdk) },
+            &ctx.retry_policy(),
+        )
+        .await?
+    } else {
+        capture_scrolled(&client, &args, &url, &spinner, &ctx.retry_policy()).await?
+    };
 
     spinner.finish_and_clear();
 
+    // --format auto: take the format from the response and name the file after it
+    let (format, output) = if is_auto_format(&args.format) {
+        let detected = detect_format_from_bytes(&image_bytes)
+            .ok_or_else(|| CliError::Other("Couldn't tell which format the API returned".to_string()))?;
+        args.format = format_extension(detected).to_string();
+        if ctx.verbose {
+            eprintln!("{} {}", "Format:".dimmed(), args.format);
+        }
+        let output = match args.output {
+            Some(ref output) => {
+                let vars = TemplateVars {
+                    url: &url,
+                    device: args.device.as_deref(),
+                    format: &args.format,
+                    index: 1,
+                };
+                let mut path = resolve_output_path(output, ctx.output_template.as_deref(), &vars)?;
+                if path.extension().is_none() {
+                    path.set_extension(&args.format);
+                }
+                Some(path)
+            }
+            None => None,
+        };
+        (detected, output)
+    } else {
+        (format, output)
+    };
+
+    // Trim before the caption so its bar isn't taken for border
+    let mut image_bytes = image_bytes;
+    let trimmed_from = if args.trim && format == ImageFormat::Pdf {
+        eprintln!("{} --trim is skipped for PDFs", "Warning:".yellow());
+        None
+    } else if args.trim {
+        trim_capture(&mut image_bytes, args.trim_tolerance, args.quality)?
+    } else {
+        None
+    };
+
+    let image_bytes = match caption {
+        Some((ref text, position, ref font)) => caption_image(&image_bytes, text, font, position, args.quality)?,
+        None => image_bytes,
+    };
+
+    // Metadata only survives in the file bytes; the clipboard and previews get bare pixels
+    let leaves_as_file = args.output.is_some() || args.upload_to.is_some() || args.base64;
+    if args.embed_metadata && !leaves_as_file {
+        eprintln!(
+            "{} --embed-metadata needs -o, --upload-to or --base64; the clipboard and terminal preview can't carry it",
+            "Warning:".yellow()
+        );
+    }
+
+    // Re-encoding drops every EXIF block and text chunk; embedding adds ours
+    let image_bytes = if args.strip_metadata || leaves_as_file {
+        apply_metadata_flags(image_bytes, &args, &url, args.device.as_deref())?
+    } else {
+        image_bytes
+    };
+
     // Get image dimensions
     let dims = TerminalImage::get_dimensions(&image_bytes).ok();
     let size = image_bytes.len();
 
+    // The sidecar names the saved file, if any, so it's only written once that file exists
+    let write_sidecar = |file: Option<&Path>| -> CliResult<()> {
+        match args.meta {
+            Some(ref path) => Sidecar {
+                metadata: ScreenshotMetadata::new(&url, args.device.as_deref()),
+                file,
+                format: &args.format,
+                width: dims.map(|(width, _)| width),
+                height: dims.map(|(_, height)| height),
+                size: size as u64,
+                options: &request,
+            }
+            .save(path),
+            None => Ok(()),
+        }
+    };
+
+    // Base64 goes to stdout alone so it can be piped; warnings stay on stderr
+    if args.base64 {
+        let mime = args.data_uri.then(|| mime_type(format));
+        println!("{}", encode_base64(&image_bytes, mime));
+        write_sidecar(None)?;
+
+        if args.warn_blank {
+            warn_if_blank(&image_bytes);
+        }
+
+        check_min_dimensions(dims, args.min_width, args.min_height)?;
+        return match baseline {
+            Some(ref baseline) => compare_with_baseline(baseline, &image_bytes, args.max_diff),
+            None => Ok(()),
+        };
+    }
+
     // Save to file if output specified
-    let output_path = if let Some(ref output) = args.output {
-        save_to_file(output, &image_bytes)?;
-        Some(output.clone())
+    let output_path = if let Some(output) = output {
+        save_to_file(&output, &image_bytes)?;
+        Some(output)
     } else {
         None
     };
+    write_sidecar(output_path.as_deref())?;
+
+    if let Some(ref destination) = args.upload_to {
+        let spinner = create_spinner("Uploading...");
+        let result = upload(destination, image_bytes.clone(), mime_type(format)).await;
+        spinner.finish_and_clear();
+        result?;
+    }
 
     // Display in terminal
     if args.should_display() {
@@ -212,22 +1235,238 @@ pub async fn execute(args: CaptureArgs, api_key: Option<String>) -> CliResult<()
     }
 
     // Print summary
-    print_summary(&url, dims, size, output_path.as_ref());
+    print_summary(&url, args.device.as_deref(), dims, args.scale, trimmed_from, size, output_path.as_ref());
+    if let Some(ref destination) = args.upload_to {
+        println!("  Uploaded to: {}", redact_query(destination).cyan());
+    }
 
-    Ok(())
+    if ctx.verbose {
+        print_remaining_quota(&client).await;
+    }
+
+    if args.warn_blank {
+        warn_if_blank(&image_bytes);
+    }
+
+    check_min_dimensions(dims, args.min_width, args.min_height)?;
+
+    match baseline {
+        Some(ref baseline) => compare_with_baseline(baseline, &image_bytes, args.max_diff),
+        None => Ok(()),
+    }
+}
+
+/// Replace a shorthand like "mobile" with its preset before anything looks the device up
+fn expand_device(args: &mut CaptureArgs, verbose: bool) {
+    if let Some(ref device) = args.device {
+        let preset = expand_device_alias(device);
+        if preset != device {
+            if verbose {
+                eprintln!("{} {} -> {}", "Device:".dimmed(), device, preset);
+            }
+            args.device = Some(preset.to_string());
+        }
+    }
+}
+
+/// Capture the viewport at each --scroll-positions offset and stitch the shots locally
+async fn capture_scrolled(
+    client: &AllscreenshotsClient,
+    args: &CaptureArgs,
+    url: &str,
+    spinner: &indicatif::ProgressBar,
+    policy: &RetryPolicy,
+) -> CliResult<Vec<u8>> {
+    let mut offsets = args.scroll_positions.clone();
+    offsets.sort_unstable();
+    offsets.dedup();
+
+    let mut frames = Vec::new();
+    for (i, offset) in offsets.iter().enumerate() {
+        spinner.set_message(format!(
+            "Capturing {} at {}px ({}/{})...",
+            url,
+            offset,
+            i + 1,
+            offsets.len()
+        ));
+
+        // The API can't scroll, so shift the page up by the offset instead
+        let mut frame_args = args.clone();
+        let shift = format!("html {{ transform: translateY(-{}px) !important; }}", offset);
+        frame_args.custom_css = Some(match args.custom_css {
+            Some(ref css) => format!("{}\n{}", css, shift),
+            None => shift,
+        });
+        let (request, format) = build_request(&frame_args, url)?;
+        if format == ImageFormat::Pdf {
+            return Err(CliError::Other("--scroll-positions needs an image format, not pdf".to_string()));
+        }
+
+        let bytes = retry_async(
+            || async { client.screenshot(&request).await.map_err(CliError::Sdk) },
+            policy,
+        )
+        .await?;
+        frames.push(bytes);
+    }
+
+    spinner.set_message("Stitching...");
+    let encoding = image::guess_format(&frames[0])
+        .map_err(|e| CliError::Other(format!("Failed to read capture: {}", e)))?;
+    let frames = crate::local_compose::decode_bytes(&frames)?;
+
+    // Offsets are CSS pixels; shots may be taken at a higher device pixel ratio
+    let css_width = args
+        .width
+        .or_else(|| args.device.as_deref().and_then(resolve_device).map(|(width, _)| width));
+    let ratio = match css_width {
+        Some(width) if width > 0 => frames[0].width() as f64 / width as f64,
+        _ => args.scale.unwrap_or(1) as f64,
+    };
+    let pixel_offsets: Vec<u32> = offsets.iter().map(|&o| (o as f64 * ratio).round() as u32).collect();
+
+    let stitched = crate::local_compose::stitch_vertical(&frames, &pixel_offsets);
+    let quality = args.quality.map(|q| q.clamp(1, 100) as u8);
+    crate::utils::encode_image(&image::DynamicImage::ImageRgba8(stitched), encoding, quality)
+}
+
+/// Injected for --hide-scrollbars: WebKit/Blink scrollbars and the standard property
+const HIDE_SCROLLBARS_CSS: &str =
+    "::-webkit-scrollbar { display: none !important; } html, body { scrollbar-width: none !important; }";
+
+/// Build the API request for the capture options; also returns the parsed format
+fn build_request(args: &CaptureArgs, url: &str) -> CliResult<(ScreenshotRequest, ImageFormat)> {
+    // Build the request
+    let mut builder = ScreenshotRequest::builder().url(url);
+
+    if let Some(ref device) = args.device {
+        builder = builder.device(device);
+    }
+
+    // Orientation needs a full size to rotate: explicit width and height, or a known preset
+    let (width, height) = match args.orientation {
+        Some(ref orientation) => {
+            let landscape = parse_orientation(orientation)?;
+            let (width, height) = match (args.width, args.height, args.device.as_deref()) {
+                (Some(w), Some(h), _) => (w, h),
+                (None, None, Some(device)) => resolve_device(device).ok_or_else(|| {
+                    CliError::Other(format!(
+                        "Unknown size for device '{}'. Pass --width and --height with --orientation",
+                        device
+                    ))
+                })?,
+                _ => {
+                    return Err(CliError::Other(
+                        "--orientation needs a --device preset or both --width and --height"
+                            .to_string(),
+                    ))
+                }
+            };
+            let (width, height) = orient(width, height, landscape);
+            (Some(width), Some(height))
+        }
+        None => (args.width, args.height),
+    };
+
+    // Apply custom viewport if width, height or scale specified
+    if width.is_some() || height.is_some() || args.scale.is_some() {
+        use allscreenshots_sdk::ViewportConfig;
+        let mut viewport = ViewportConfig::default();
+        if let Some(width) = width {
+            viewport.width = Some(width as i32);
+        }
+        if let Some(height) = height {
+            viewport.height = Some(height as i32);
+        }
+        if let Some(scale) = args.scale {
+            viewport.device_scale_factor = Some(scale as i32);
+        }
+        builder = builder.viewport(viewport);
+    }
+
+    // auto leaves the choice to the API; capture_one names the file after the bytes it gets
+    let format = if is_auto_format(&args.format) {
+        ImageFormat::Png
+    } else {
+        let format = parse_format(&args.format)?;
+        builder = builder.format(format);
+        format
+    };
+
+    if args.full_page {
+        builder = builder.full_page(true);
+    }
+
+    if let Some(quality) = args.quality {
+        builder = builder.quality(quality);
+    }
+
+    if let Some(delay) = args.delay {
+        builder = builder.delay(delay);
+    }
+
+    if let Some(wait_for) = combine_selectors(&args.wait_for, args.wait_for_any) {
+        builder = builder.wait_for(wait_for);
+    }
+
+    if let Some(ref wait_until) = args.wait_until {
+        let conditions = parse_wait_until(wait_until)?;
+        builder = builder.wait_until(strictest_wait_until(&conditions));
+    }
+
+    if args.dark_mode {
+        builder = builder.dark_mode(true);
+    }
+
+    if args.block_ads {
+        builder = builder.block_ads(true);
+    }
+
+    if args.block_cookies {
+        builder = builder.block_cookie_banners(true);
+    }
+
+    if let Some(ref level) = args.block_level {
+        let block_level = parse_block_level(level)?;
+        builder = builder.block_level(block_level);
+    }
+
+    if let Some(ref selector) = args.selector {
+        builder = builder.selector(selector);
+    }
+
+    // Scrollbar CSS, then hidden selectors, then --custom-css last so it can override both
+    let hide_scrollbars = args.hide_scrollbars || (args.full_page && !args.show_scrollbars);
+    let css: Vec<String> = [
+        hide_scrollbars.then(|| HIDE_SCROLLBARS_CSS.to_string()),
+        hide_css(&args.hide),
+        args.custom_css.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !css.is_empty() {
+        builder = builder.custom_css(css.join("\n"));
+    }
+
+    let request = builder.build().map_err(|e| CliError::Other(e.to_string()))?;
+
+    Ok((request, format))
 }
 
 /// Quick capture for default command (allscreenshots <URL>)
 pub async fn quick_capture(
     url: &str,
-    api_key: Option<&str>,
+    ctx: &Context,
     output: Option<&str>,
     device: Option<&str>,
     full_page: bool,
     should_display: bool,
 ) -> CliResult<()> {
-    let api_key = api_key.map(String::from).ok_or(CliError::NoApiKey)?;
+    let client = ctx.client()?;
     let url = normalize_url(url)?;
+    let device = device.map(expand_device_alias);
 
     // Build the request
     let mut builder = ScreenshotRequest::builder().url(&url);
@@ -240,13 +1479,30 @@ pub async fn quick_capture(
         builder = builder.full_page(true);
     }
 
+    if let Some(css) = ctx.block_css() {
+        builder = builder.custom_css(css);
+    }
+
     let request = builder.build().map_err(|e| CliError::Other(e.to_string()))?;
 
-    // Create client and capture
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+    let output = output
+        .map(|output| {
+            let vars = TemplateVars {
+                url: &url,
+                device,
+                format: "png",
+                index: 1,
+            };
+            resolve_output_path(Path::new(output), ctx.output_template.as_deref(), &vars)
+        })
+        .transpose()?;
 
     let spinner = create_spinner(&format!("Capturing {}...", url));
-    let image_bytes = client.screenshot(&request).await.map_err(CliError::Sdk)?;
+    let image_bytes = retry_async(
+        || async { client.screenshot(&request).await.map_err(CliError::Sdk) },
+        &ctx.retry_policy(),
+    )
+    .await?;
     spinner.finish_and_clear();
 
     // Get image dimensions
@@ -254,8 +1510,7 @@ pub async fn quick_capture(
     let size = image_bytes.len();
 
     // Save to file if output specified
-    let output_path = if let Some(output) = output {
-        let path = PathBuf::from(output);
+    let output_path = if let Some(path) = output {
         save_to_file(&path, &image_bytes)?;
         Some(path)
     } else {
@@ -271,37 +1526,79 @@ pub async fn quick_capture(
     }
 
     // Print summary
-    print_summary(&url, dims, size, output_path.as_ref());
+    print_summary(&url, device, dims, None, None, size, output_path.as_ref());
+
+    if ctx.verbose {
+        print_remaining_quota(&client).await;
+    }
 
     Ok(())
 }
 
+fn is_auto_format(s: &str) -> bool {
+    s.eq_ignore_ascii_case("auto")
+}
+
 fn parse_format(s: &str) -> CliResult<ImageFormat> {
-    match s.to_lowercase().as_str() {
-        "png" => Ok(ImageFormat::Png),
-        "jpeg" | "jpg" => Ok(ImageFormat::Jpeg),
-        "webp" => Ok(ImageFormat::Webp),
-        "pdf" => Ok(ImageFormat::Pdf),
-        _ => Err(CliError::Other(format!(
-            "Invalid format '{}'. Use: png, jpeg, webp, or pdf",
-            s
+    match crate::utils::find_format(s) {
+        Some(info) => Ok(info.format),
+        None => Err(CliError::Other(format!(
+            "Invalid format '{}'. Use: {}, or auto (see `allscreenshots formats`)",
+            s,
+            crate::utils::FORMATS.iter().map(|info| info.name).collect::<Vec<_>>().join(", ")
         ))),
     }
 }
 
-fn parse_wait_until(s: &str) -> CliResult<WaitUntil> {
+fn mime_type(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Png => "image/png",
+        ImageFormat::Jpeg | ImageFormat::Jpg => "image/jpeg",
+        ImageFormat::Webp => "image/webp",
+        ImageFormat::Pdf => "application/pdf",
+    }
+}
+
+/// Parse an orientation; returns true for landscape
+fn parse_orientation(s: &str) -> CliResult<bool> {
     match s.to_lowercase().as_str() {
-        "load" => Ok(WaitUntil::Load),
-        "domcontentloaded" => Ok(WaitUntil::DomContentLoaded),
-        "networkidle" => Ok(WaitUntil::NetworkIdle),
-        "commit" => Ok(WaitUntil::Commit),
+        "landscape" => Ok(true),
+        "portrait" => Ok(false),
         _ => Err(CliError::Other(format!(
-            "Invalid wait_until '{}'. Use: load, domcontentloaded, networkidle, or commit",
+            "Invalid orientation '{}'. Use: portrait or landscape",
             s
         ))),
     }
 }
 
+/// Parse a comma-separated --wait-until list, checking every condition
+fn parse_wait_until(s: &str) -> CliResult<Vec<WaitUntil>> {
+    s.split(',')
+        .map(|token| match token.trim().to_lowercase().as_str() {
+            "load" => Ok(WaitUntil::Load),
+            "domcontentloaded" => Ok(WaitUntil::DomContentLoaded),
+            "networkidle" => Ok(WaitUntil::NetworkIdle),
+            "commit" => Ok(WaitUntil::Commit),
+            _ => Err(CliError::Other(format!(
+                "Invalid wait_until '{}'. Use: load, domcontentloaded, networkidle, or commit",
+                token.trim()
+            ))),
+        })
+        .collect()
+}
+
+/// The API takes one condition; the latest to fire implies all the earlier ones,
+/// so waiting for it satisfies every condition in the list
+fn strictest_wait_until(conditions: &[WaitUntil]) -> WaitUntil {
+    let rank = |condition: &WaitUntil| match condition {
+        WaitUntil::Commit => 0,
+        WaitUntil::DomContentLoaded => 1,
+        WaitUntil::Load => 2,
+        WaitUntil::NetworkIdle => 3,
+    };
+    conditions.iter().copied().max_by_key(rank).unwrap_or_default()
+}
+
 fn parse_block_level(s: &str) -> CliResult<BlockLevel> {
     match s.to_lowercase().as_str() {
         "none" => Ok(BlockLevel::None),
@@ -343,12 +1640,190 @@ fn copy_to_clipboard(image_bytes: &[u8]) -> CliResult<()> {
     Ok(())
 }
 
-fn print_summary(url: &str, dims: Option<(u32, u32)>, size: usize, output: Option<&PathBuf>) {
+/// Re-encode the image in its own format; formats without metadata are left as-is
+fn strip_metadata(image_bytes: Vec<u8>, quality: Option<i32>) -> CliResult<Vec<u8>> {
+    let format = match image::guess_format(&image_bytes) {
+        Ok(format @ (image::ImageFormat::Png | image::ImageFormat::Jpeg | image::ImageFormat::WebP)) => {
+            format
+        }
+        _ => return Ok(image_bytes),
+    };
+
+    // Keep JPEG re-encoding close to the original unless a quality was given
+    let quality = quality.map_or(95, |q| q.clamp(1, 100) as u8);
+    crate::utils::reencode_image(&image_bytes, format, Some(quality))
+}
+
+/// Crop a uniform border off an encoded capture in place; returns the size before
+/// trimming, or None if there was nothing to cut
+fn trim_capture(image_bytes: &mut Vec<u8>, tolerance: u8, quality: Option<i32>) -> CliResult<Option<(u32, u32)>> {
+    let format = image::guess_format(image_bytes)
+        .map_err(|e| CliError::Other(format!("Failed to read capture: {}", e)))?;
+    let img = image::load_from_memory(image_bytes)
+        .map_err(|e| CliError::Other(format!("Failed to decode image: {}", e)))?;
+
+    let Some((x, y, width, height)) = crate::utils::trim_bounds(&img.to_rgba8(), tolerance) else {
+        return Ok(None);
+    };
+    let quality = quality.map_or(95, |q| q.clamp(1, 100) as u8);
+    *image_bytes = crate::utils::encode_image(&img.crop_imm(x, y, width, height), format, Some(quality))?;
+    Ok(Some((img.width(), img.height())))
+}
+
+/// Draw the --annotate caption and re-encode in the capture's own format
+fn caption_image(
+    image_bytes: &[u8],
+    text: &str,
+    font: &ab_glyph::FontVec,
+    position: annotate::CaptionPosition,
+    quality: Option<i32>,
+) -> CliResult<Vec<u8>> {
+    let format = image::guess_format(image_bytes)
+        .map_err(|e| CliError::Other(format!("Failed to read capture: {}", e)))?;
+    let img = image::load_from_memory(image_bytes)
+        .map_err(|e| CliError::Other(format!("Failed to decode image: {}", e)))?;
+
+    let captioned = annotate::add_caption(&img.to_rgba8(), text, font, position);
+    let quality = quality.map_or(95, |q| q.clamp(1, 100) as u8);
+    crate::utils::encode_image(&image::DynamicImage::ImageRgba8(captioned), format, Some(quality))
+}
+
+/// Below this share of non-background pixels the page is considered blank
+const BLANK_THRESHOLD: f64 = 0.005;
+
+/// Warn when the capture looks like a failed render (flat, single-color image)
+fn warn_if_blank(image_bytes: &[u8]) {
+    match crate::utils::content_ratio(image_bytes) {
+        Ok(ratio) if ratio < BLANK_THRESHOLD => {
+            eprintln!(
+                "{} Image looks blank ({:.1}% non-background pixels)",
+                "Warning:".yellow(),
+                ratio * 100.0
+            );
+            eprintln!(
+                "  {}",
+                "The page may have failed to load or the selector matched nothing".dimmed()
+            );
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("{} Couldn't check for a blank image: {}", "Warning:".yellow(), e),
+    }
+}
+
+/// Only presigned HTTPS URLs are supported; s3:// would need AWS credentials
+fn check_upload_destination(destination: &str) -> CliResult<()> {
+    if destination.starts_with("s3://") {
+        return Err(CliError::Other(
+            "s3:// destinations aren't supported. Pass a presigned HTTPS PUT URL \
+             generated with your storage provider's SDK"
+                .to_string(),
+        ));
+    }
+    // Presigned URLs carry their credentials in the query string, so never send them in the clear
+    if destination.starts_with("http://") {
+        return Err(CliError::Other(format!(
+            "Refusing to upload over plain HTTP to '{}'. Use a presigned HTTPS URL",
+            redact_query(destination)
+        )));
+    }
+    if !destination.starts_with("https://") {
+        return Err(CliError::Other(format!(
+            "Invalid upload destination '{}'. Use a presigned HTTPS URL",
+            redact_query(destination)
+        )));
+    }
+    Ok(())
+}
+
+/// Report how the capture differs from the baseline; changes beyond `max_diff` percent fail
+fn compare_with_baseline(baseline: &[u8], image_bytes: &[u8], max_diff: f64) -> CliResult<()> {
+    let diff = diff_images(baseline, image_bytes)?;
+    if diff.ratio * 100.0 > max_diff {
+        return Err(CliError::Other(format!(
+            "Changed from baseline: {} (allowed {}%)",
+            diff.describe(),
+            max_diff
+        )));
+    }
+    println!("  {} Matches baseline ({})", icons::success().green(), diff.describe());
+    Ok(())
+}
+
+/// Fail if the image is smaller than the requested minimum size
+fn check_min_dimensions(
+    dims: Option<(u32, u32)>,
+    min_width: Option<u32>,
+    min_height: Option<u32>,
+) -> CliResult<()> {
+    if min_width.is_none() && min_height.is_none() {
+        return Ok(());
+    }
+
+    let (width, height) = dims.ok_or_else(|| {
+        CliError::Other("Can't check minimum dimensions: failed to read image size".to_string())
+    })?;
+
+    let too_narrow = min_width.is_some_and(|min| width < min);
+    let too_short = min_height.is_some_and(|min| height < min);
+
+    if too_narrow || too_short {
+        let expected = |min: Option<u32>| min.map_or("any".to_string(), |m| m.to_string());
+        return Err(CliError::Other(format!(
+            "Image is smaller than expected: got {}x{}, expected at least {}x{}",
+            width,
+            height,
+            expected(min_width),
+            expected(min_height)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Show the remaining screenshot quota; failures are ignored so they never
+/// hide a successful capture
+async fn print_remaining_quota(client: &AllscreenshotsClient) {
+    let quota = tokio::time::timeout(Duration::from_secs(5), client.get_quota()).await;
+
+    if let Ok(Ok(quota)) = quota {
+        println!(
+            "  Quota: {} of {} screenshots remaining{}",
+            quota.screenshots.remaining,
+            quota.screenshots.limit,
+            quota
+                .period_ends
+                .map(|end| format!(" (resets {})", end))
+                .unwrap_or_default()
+                .dimmed()
+        );
+    }
+}
+
+fn print_summary(
+    url: &str,
+    device: Option<&str>,
+    dims: Option<(u32, u32)>,
+    scale: Option<u32>,
+    trimmed_from: Option<(u32, u32)>,
+    size: usize,
+    output: Option<&PathBuf>,
+) {
     println!("{}", "Screenshot captured!".green().bold());
     println!("  URL: {}", url.dimmed());
 
-    if let Some((w, h)) = dims {
-        println!("  Size: {}x{}", w, h);
+    if let Some(device) = device {
+        println!("  Device: {}", device);
+    }
+
+    if let Some(dims) = dims {
+        match trimmed_from {
+            Some((width, height)) => println!(
+                "  Size: {} {}",
+                describe_size(dims, scale),
+                format!("(trimmed from {}x{})", width, height).dimmed()
+            ),
+            None => println!("  Size: {}", describe_size(dims, scale)),
+        }
     }
 
     println!(
@@ -357,6 +1832,54 @@ fn print_summary(url: &str, dims: Option<(u32, u32)>, size: usize, output: Optio
     );
 
     if let Some(path) = output {
-        println!("  Saved to: {}", path.display().to_string().cyan());
+        println!("  Saved to: {}", file_hyperlink(path, path.display().to_string().cyan()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wait_until_list() {
+        assert_eq!(
+            parse_wait_until("load, NetworkIdle").unwrap(),
+            vec![WaitUntil::Load, WaitUntil::NetworkIdle]
+        );
+        assert!(parse_wait_until("load,idle").is_err());
+        assert!(parse_wait_until("load,").is_err());
+    }
+
+    #[test]
+    fn test_modified_baseline() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let modified: chrono::DateTime<chrono::Utc> = file.as_file().metadata().unwrap().modified().unwrap().into();
+        assert_eq!(modified_baseline(file.path().to_str().unwrap()).unwrap(), modified);
+
+        assert!(modified_baseline("2024-05-01").is_ok());
+        assert!(modified_baseline("missing.png").is_err());
+    }
+
+    #[test]
+    fn test_check_upload_destination() {
+        assert!(check_upload_destination("https://bucket.s3.amazonaws.com/shot.png?X-Amz-Signature=abc").is_ok());
+        assert!(check_upload_destination("s3://bucket/shot.png").is_err());
+
+        let err = check_upload_destination("http://bucket.example.com/shot.png?signature=abc").unwrap_err();
+        assert!(err.to_string().contains("plain HTTP"));
+        assert!(!err.to_string().contains("signature"));
+    }
+
+    #[test]
+    fn test_full_page_path() {
+        assert_eq!(full_page_path(Path::new("shots/home.png")), PathBuf::from("shots/home.full.png"));
+        assert_eq!(full_page_path(Path::new("home")), PathBuf::from("home.full"));
+    }
+
+    #[test]
+    fn test_strictest_wait_until() {
+        let conditions = parse_wait_until("networkidle,domcontentloaded,load").unwrap();
+        assert_eq!(strictest_wait_until(&conditions), WaitUntil::NetworkIdle);
+        assert_eq!(strictest_wait_until(&[WaitUntil::Commit]), WaitUntil::Commit);
     }
 }
@@ -1,4 +1,4 @@
-use clap::{Command, CommandFactory};
+use clap::Command;
 use clap_complete::{generate, Shell};
 use std::io;
 use crate::error::{CliError, CliResult};
@@ -66,3 +66,17 @@ pub fn parse_shell(s: &str) -> CliResult<Shell> {
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shell_round_trip() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell, Shell::Elvish] {
+            assert_eq!(parse_shell(&shell.to_string()).unwrap(), shell);
+        }
+        assert_eq!(parse_shell("PS").unwrap(), Shell::PowerShell);
+        assert!(parse_shell("tcsh").is_err());
+    }
+}
@@ -1,8 +1,11 @@
-use crate::display::{create_spinner, TerminalImage};
+use crate::context::Context;
+use crate::display::{create_spinner, file_hyperlink, hyperlink, TerminalImage};
 use crate::error::{CliError, CliResult};
-use crate::utils::{normalize_url, save_to_file};
+use crate::local_compose::{self, ComposeOptions, LocalLayout};
+use crate::retry::{is_connect_failure, retry_async, RetryPolicy};
+use crate::utils::{download, encode_image, normalize_url, reencode_image, save_to_file};
 use allscreenshots_sdk::{
-    AllscreenshotsClient, CaptureItem, ComposeOutputConfig, ComposeRequest, ImageFormat, LayoutType,
+    CaptureDefaults, CaptureItem, ComposeOutputConfig, ComposeRequest, ImageFormat, JobStatus, LayoutType,
 };
 use clap::Args;
 use colored::Colorize;
@@ -10,10 +13,31 @@ use std::path::PathBuf;
 
 #[derive(Args, Debug)]
 pub struct ComposeArgs {
-    /// URLs to compose (2-20 URLs)
-    #[arg(required = true, num_args = 2..=20)]
+    /// URLs to compose (2-20 URLs), image files with --from-files, or job IDs with --from-jobs
+    #[arg(required_unless_present = "recent", num_args = 2..=20)]
     pub urls: Vec<String>,
 
+    /// Compose local image files instead of capturing URLs
+    #[arg(long, requires = "output")]
+    pub from_files: bool,
+
+    /// Compose the results of existing jobs instead of capturing URLs
+    #[arg(long, conflicts_with = "from_files")]
+    pub from_jobs: bool,
+
+    /// Compose the results of the last N completed jobs
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u8).range(2..=20),
+        conflicts_with_all = ["urls", "from_files", "from_jobs"]
+    )]
+    pub recent: Option<u8>,
+
+    /// Threads for decoding images with --from-files (default: one per core)
+    #[arg(long, requires = "from_files")]
+    pub threads: Option<usize>,
+
     /// Output file path
     #[arg(short, long)]
     pub output: Option<PathBuf>,
@@ -65,9 +89,14 @@ pub struct ComposeArgs {
     /// Don't display in terminal
     #[arg(long)]
     pub no_display: bool,
+
+    /// Re-encode the downloaded result locally: png, jpeg, webp
+    #[arg(long, value_name = "FORMAT", requires = "output")]
+    pub download_format: Option<String>,
 }
 
 impl ComposeArgs {
+    /// Check if we should display the image
     pub fn should_display(&self) -> bool {
         if self.no_display {
             return false;
@@ -76,8 +105,15 @@ impl ComposeArgs {
     }
 }
 
-pub async fn execute(args: ComposeArgs, api_key: Option<String>) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
+pub async fn execute(args: ComposeArgs, ctx: &Context) -> CliResult<()> {
+    if args.from_files {
+        return compose_files(&args);
+    }
+    if args.from_jobs || args.recent.is_some() {
+        return compose_jobs(&args, ctx).await;
+    }
+
+    let client = ctx.client()?;
 
     // Normalize URLs
     let urls: Vec<String> = args
@@ -108,6 +144,15 @@ pub async fn execute(args: ComposeArgs, api_key: Option<String>) -> CliResult<()
     // Parse layout
     let layout = parse_layout(&args.layout)?;
 
+    let download_format = args
+        .download_format
+        .as_deref()
+        .map(parse_download_format)
+        .transpose()?;
+    if let Some(format) = download_format {
+        warn_lossless_webp(format, args.quality);
+    }
+
     // Parse format
     let format = match args.format.to_lowercase().as_str() {
         "png" => ImageFormat::Png,
@@ -117,9 +162,11 @@ pub async fn execute(args: ComposeArgs, api_key: Option<String>) -> CliResult<()
     };
 
     // Build output config
-    let mut output_config = ComposeOutputConfig::default();
-    output_config.layout = Some(layout);
-    output_config.format = Some(format);
+    let mut output_config = ComposeOutputConfig {
+        layout: Some(layout),
+        format: Some(format),
+        ..Default::default()
+    };
 
     if let Some(columns) = args.columns {
         output_config.columns = Some(columns);
@@ -138,15 +185,27 @@ pub async fn execute(args: ComposeArgs, api_key: Option<String>) -> CliResult<()
     }
 
     // Build request
-    let request = ComposeRequest::with_captures(captures).with_output(output_config);
-
-    // Create client
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+    let mut request = ComposeRequest::with_captures(captures).with_output(output_config);
+    if let Some(css) = ctx.block_css() {
+        request = request.with_defaults(CaptureDefaults {
+            custom_css: Some(css),
+            ..Default::default()
+        });
+    }
 
+    // Composing isn't idempotent, so only retry when the request never reached the API
+    let policy = RetryPolicy {
+        retry_if: is_connect_failure,
+        ..ctx.retry_policy()
+    };
     let spinner = create_spinner("Composing screenshots...");
 
     // Execute compose
-    let result = client.compose(&request).await.map_err(CliError::Sdk)?;
+    let result = retry_async(
+        || async { client.compose(&request).await.map_err(CliError::Sdk) },
+        &policy,
+    )
+    .await?;
 
     spinner.finish_and_clear();
 
@@ -174,21 +233,195 @@ pub async fn execute(args: ComposeArgs, api_key: Option<String>) -> CliResult<()
 
     // If we have a URL, show it
     if let Some(ref url) = result.url {
-        println!("  Result URL: {}", url.cyan());
-
-        // Save to file if output specified
-        if let Some(ref output) = args.output {
-            println!("  To download, use: curl -o {} '{}'", output.display(), url);
-        }
+        println!("  Result URL: {}", hyperlink(url, url.cyan()));
     }
 
     if let Some(ref storage_url) = result.storage_url {
         println!("  Storage URL: {}", storage_url.cyan());
     }
 
+    let should_display = args.should_display();
+    if args.output.is_none() && !should_display {
+        return Ok(());
+    }
+
+    let Some(ref url) = result.url else {
+        return Ok(());
+    };
+
+    // Download the composite to save or display it
+    let spinner = create_spinner("Downloading result...");
+    let mut image_bytes = download(url).await?;
+
+    if let Some(format) = download_format {
+        spinner.set_message("Re-encoding result...");
+        let quality = args.quality.map(|q| q.clamp(1, 100) as u8);
+        image_bytes = reencode_image(&image_bytes, format, quality)?;
+    }
+
+    spinner.finish_and_clear();
+
+    if let Some(ref output) = args.output {
+        save_to_file(output, &image_bytes)?;
+        println!(
+            "  Saved to: {} ({})",
+            file_hyperlink(output, output.display().to_string().cyan()),
+            crate::utils::format_file_size(image_bytes.len() as u64)
+        );
+    }
+
+    if should_display {
+        println!();
+        let display = TerminalImage::new();
+        display.display_bytes(&image_bytes)?;
+        println!();
+    }
+
     Ok(())
 }
 
+/// Compose local image files without calling the API
+fn compose_files(args: &ComposeArgs) -> CliResult<()> {
+    let paths: Vec<PathBuf> = args.urls.iter().map(PathBuf::from).collect();
+
+    println!(
+        "{} {} images",
+        "Composing".cyan().bold(),
+        paths.len()
+    );
+
+    let spinner = create_spinner("Decoding images...");
+    let images = local_compose::decode_images(&paths, args.threads)?;
+    spinner.finish_and_clear();
+
+    compose_local(args, &images)
+}
+
+/// Download finished job results and compose them locally, without new captures
+async fn compose_jobs(args: &ComposeArgs, ctx: &Context) -> CliResult<()> {
+    let client = ctx.client()?;
+    let policy = ctx.retry_policy();
+
+    let job_ids = match args.recent {
+        Some(count) => {
+            let mut jobs: Vec<_> = retry_async(
+                || async { client.list_jobs().await.map_err(CliError::Sdk) },
+                &policy,
+            )
+            .await?
+            .into_iter()
+            .filter(|job| job.status == JobStatus::Completed)
+            .collect();
+
+            // Newest first to pick them, then oldest first on the canvas
+            jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            jobs.truncate(count as usize);
+            if jobs.len() < 2 {
+                return Err(CliError::Other(format!(
+                    "Found {} completed job(s); at least 2 are needed to compose",
+                    jobs.len()
+                )));
+            }
+            jobs.into_iter().rev().map(|job| job.id).collect()
+        }
+        None => args.urls.clone(),
+    };
+
+    println!(
+        "{} {} job results",
+        "Composing".cyan().bold(),
+        job_ids.len()
+    );
+
+    let spinner = create_spinner("Downloading job results...");
+    let mut buffers = Vec::with_capacity(job_ids.len());
+    for (i, id) in job_ids.iter().enumerate() {
+        spinner.set_message(format!("Downloading job result {}/{}...", i + 1, job_ids.len()));
+        let bytes = retry_async(
+            || async { client.get_job_result(id).await.map_err(CliError::Sdk) },
+            &policy,
+        )
+        .await
+        .map_err(|e| CliError::Other(format!("Failed to download job {}: {}", id, e)))?;
+        buffers.push(bytes);
+    }
+
+    spinner.set_message("Decoding images...");
+    let images = local_compose::decode_bytes(&buffers)?;
+    spinner.finish_and_clear();
+
+    compose_local(args, &images)
+}
+
+/// Compose decoded images on one canvas, then save and/or display the result
+fn compose_local(args: &ComposeArgs, images: &[image::DynamicImage]) -> CliResult<()> {
+    let mut options = ComposeOptions {
+        layout: LocalLayout::parse(&args.layout)?,
+        columns: args.columns.map(|c| c.max(1) as u32),
+        spacing: args.spacing.unwrap_or(0).max(0) as u32,
+        padding: args.padding.unwrap_or(0).max(0) as u32,
+        ..Default::default()
+    };
+    if let Some(ref bg) = args.background {
+        options.background = local_compose::parse_background(bg)?;
+    }
+    let format = parse_download_format(args.download_format.as_deref().unwrap_or(&args.format))?;
+    warn_lossless_webp(format, args.quality);
+
+    let spinner = create_spinner("Composing images...");
+    let canvas = local_compose::compose(images, &options);
+    let quality = args.quality.map(|q| q.clamp(1, 100) as u8);
+    let image_bytes = encode_image(&image::DynamicImage::ImageRgba8(canvas), format, quality)?;
+    spinner.finish_and_clear();
+
+    println!("{}", "Composition complete!".green().bold());
+    println!("  Layout: {}", args.layout);
+    if let Ok((w, h)) = TerminalImage::get_dimensions(&image_bytes) {
+        println!("  Size: {}x{}", w, h);
+    }
+
+    if let Some(ref output) = args.output {
+        save_to_file(output, &image_bytes)?;
+        println!(
+            "  Saved to: {} ({})",
+            file_hyperlink(output, output.display().to_string().cyan()),
+            crate::utils::format_file_size(image_bytes.len() as u64)
+        );
+    }
+
+    if args.should_display() {
+        println!();
+        let display = TerminalImage::new();
+        display.display_bytes(&image_bytes)?;
+        println!();
+    }
+
+    Ok(())
+}
+
+/// The local WebP encoder is lossless only, so --quality can't shrink the file
+fn warn_lossless_webp(format: image::ImageFormat, quality: Option<i32>) {
+    if format == image::ImageFormat::WebP && quality.is_some() {
+        eprintln!(
+            "{} --quality has no effect on WebP encoded locally; it is saved lossless and may be larger. \
+             Use jpeg for a smaller lossy file",
+            "Warning:".yellow()
+        );
+    }
+}
+
+fn parse_download_format(s: &str) -> CliResult<image::ImageFormat> {
+    match s.to_lowercase().as_str() {
+        "png" => Ok(image::ImageFormat::Png),
+        "jpeg" | "jpg" => Ok(image::ImageFormat::Jpeg),
+        "webp" => Ok(image::ImageFormat::WebP),
+        _ => Err(CliError::Other(format!(
+            "Invalid download format '{}'. Use: png, jpeg, or webp",
+            s
+        ))),
+    }
+}
+
 fn parse_layout(s: &str) -> CliResult<LayoutType> {
     match s.to_lowercase().as_str() {
         "grid" => Ok(LayoutType::Grid),
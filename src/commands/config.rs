@@ -34,6 +34,15 @@ pub enum ConfigSubcommand {
         key: String,
         /// Configuration value
         value: String,
+        /// Store the value even if it isn't a known device or format
+        #[arg(long)]
+        force: bool,
+        /// Add the value to a list key instead of replacing it
+        #[arg(long, conflicts_with = "remove")]
+        append: bool,
+        /// Remove the value from a list key
+        #[arg(long)]
+        remove: bool,
     },
 
     /// Get a configuration value
@@ -49,7 +58,24 @@ pub async fn execute(cmd: ConfigCommand) -> CliResult<()> {
         ConfigSubcommand::Show => show_config(),
         ConfigSubcommand::Path => show_path(),
         ConfigSubcommand::RemoveAuthToken => remove_auth_token(),
-        ConfigSubcommand::Set { key, value } => set_config(&key, &value),
+        ConfigSubcommand::Set {
+            key,
+            value,
+            force,
+            append,
+            remove,
+        } => {
+            if key == "defaults.block_selectors" {
+                set_list(&key, &value, append, remove)
+            } else if append || remove {
+                Err(CliError::Other(format!(
+                    "--append and --remove only work with list keys (defaults.block_selectors), not {}",
+                    key
+                )))
+            } else {
+                set_config(&key, &value, force)
+            }
+        }
         ConfigSubcommand::Get { key } => get_config(&key),
     }
 }
@@ -98,6 +124,9 @@ fn show_config() -> CliResult<()> {
             println!("  api_key = {}", "(not set)".dimmed());
         }
     }
+    if let Some(ref base) = config.auth.api_base {
+        println!("  api_base = \"{}\"", base);
+    }
 
     // Defaults
     println!("\n{}", "[defaults]".cyan());
@@ -110,9 +139,24 @@ fn show_config() -> CliResult<()> {
     if let Some(ref dir) = config.defaults.output_dir {
         println!("  output_dir = \"{}\"", dir);
     }
+    if let Some(ref template) = config.defaults.output_template {
+        println!("  output_template = \"{}\"", template);
+    }
     if let Some(display) = config.defaults.display {
         println!("  display = {}", display);
     }
+    if let Some(ref interval) = config.defaults.poll_interval {
+        println!("  poll_interval = \"{}\"", interval);
+    }
+    if let Some(retries) = config.defaults.retries {
+        println!("  retries = {}", retries);
+    }
+    if let Some(strict) = config.defaults.strict_url {
+        println!("  strict_url = {}", strict);
+    }
+    if !config.defaults.block_selectors.is_empty() {
+        println!("  block_selectors = {:?}", config.defaults.block_selectors);
+    }
 
     // Display
     println!("\n{}", "[display]".cyan());
@@ -125,6 +169,9 @@ fn show_config() -> CliResult<()> {
     if let Some(height) = config.display.height {
         println!("  height = {}", height);
     }
+    if let Some(max_width) = config.display.max_width {
+        println!("  max_width = {}", max_width);
+    }
 
     println!();
 
@@ -171,16 +218,24 @@ fn remove_auth_token() -> CliResult<()> {
     Ok(())
 }
 
-fn set_config(key: &str, value: &str) -> CliResult<()> {
+fn set_config(key: &str, value: &str, force: bool) -> CliResult<()> {
     let mut config = Config::load().map_err(CliError::Config)?;
+    let value = if force { value.to_string() } else { validate_value(key, value)? };
+    let value = value.as_str();
 
     match key {
+        "auth.api_base" => {
+            config.auth.api_base = Some(crate::utils::normalize_api_base(value)?);
+        }
         "defaults.device" => {
             config.defaults.device = Some(value.to_string());
         }
         "defaults.format" => {
             config.defaults.format = Some(value.to_string());
         }
+        "defaults.output_template" => {
+            config.defaults.output_template = Some(value.to_string());
+        }
         "defaults.output_dir" => {
             config.defaults.output_dir = Some(value.to_string());
         }
@@ -189,6 +244,19 @@ fn set_config(key: &str, value: &str) -> CliResult<()> {
                 CliError::Other("Value must be 'true' or 'false'".to_string())
             })?);
         }
+        "defaults.poll_interval" => {
+            config.defaults.poll_interval = Some(value.to_string());
+        }
+        "defaults.retries" => {
+            config.defaults.retries = Some(value.parse().map_err(|_| {
+                CliError::Other("Value must be a number".to_string())
+            })?);
+        }
+        "defaults.strict_url" => {
+            config.defaults.strict_url = Some(value.parse().map_err(|_| {
+                CliError::Other("Value must be 'true' or 'false'".to_string())
+            })?);
+        }
         "display.protocol" => {
             config.display.protocol = Some(value.to_string());
         }
@@ -202,9 +270,14 @@ fn set_config(key: &str, value: &str) -> CliResult<()> {
                 CliError::Other("Value must be a number".to_string())
             })?);
         }
+        "display.max_width" => {
+            config.display.max_width = Some(value.parse().map_err(|_| {
+                CliError::Other("Value must be a number".to_string())
+            })?);
+        }
         _ => {
             return Err(CliError::Other(format!(
-                "Unknown config key: {}. Valid keys: defaults.device, defaults.format, defaults.output_dir, defaults.display, display.protocol, display.width, display.height",
+                "Unknown config key: {}. Valid keys: auth.api_base, defaults.device, defaults.format, defaults.output_dir, defaults.output_template, defaults.display, defaults.poll_interval, defaults.retries, defaults.strict_url, defaults.block_selectors, display.protocol, display.width, display.height, display.max_width",
                 key
             )));
         }
@@ -217,18 +290,88 @@ fn set_config(key: &str, value: &str) -> CliResult<()> {
     Ok(())
 }
 
+/// Replace, append to or remove from a list value; an empty value clears the list
+fn set_list(key: &str, value: &str, append: bool, remove: bool) -> CliResult<()> {
+    let mut config = Config::load().map_err(CliError::Config)?;
+    let list = &mut config.defaults.block_selectors;
+    let value = value.trim();
+
+    if remove {
+        let before = list.len();
+        list.retain(|item| item != value);
+        if list.len() == before {
+            return Err(CliError::Other(format!("\"{}\" isn't in {}", value, key)));
+        }
+    } else if append {
+        if value.is_empty() {
+            return Err(CliError::Other("Can't append an empty selector".to_string()));
+        }
+        if !list.iter().any(|item| item == value) {
+            list.push(value.to_string());
+        }
+    } else {
+        list.clear();
+        if !value.is_empty() {
+            list.push(value.to_string());
+        }
+    }
+
+    let summary = format!("{:?}", list);
+    config.save().map_err(CliError::Config)?;
+
+    println!("{} {} = {}", "Set".green(), key, summary);
+
+    Ok(())
+}
+
+/// Catch typos in defaults that would otherwise only fail at capture time
+fn validate_value(key: &str, value: &str) -> CliResult<String> {
+    match key {
+        "defaults.device" => match crate::utils::known_device(value) {
+            Some(device) => Ok(device.to_string()),
+            None => Err(CliError::Other(format!(
+                "Unknown device: \"{}\". Run `allscreenshots devices` for the list, or pass --force to store it anyway",
+                value
+            ))),
+        },
+        "defaults.format" if crate::utils::format_names().contains(&value) => Ok(value.to_string()),
+        "defaults.format" => Err(CliError::Other(format!(
+            "Unknown format: \"{}\". Valid formats: {} (or pass --force to store it anyway)",
+            value,
+            crate::utils::format_names().join(", ")
+        ))),
+        "defaults.poll_interval" => crate::utils::parse_duration(value)
+            .map(|_| value.to_string())
+            .map_err(|_| {
+                CliError::Other(format!(
+                    "Invalid poll interval: \"{}\". Use a duration like \"1s\" or \"500ms\"",
+                    value
+                ))
+            }),
+        _ => Ok(value.to_string()),
+    }
+}
+
 fn get_config(key: &str) -> CliResult<()> {
     let config = Config::load().map_err(CliError::Config)?;
 
     let value: Option<String> = match key {
         "auth.api_key" => config.auth.api_key.map(|k| Config::mask_api_key(&k)),
+        "auth.api_base" => config.auth.api_base,
         "defaults.device" => config.defaults.device,
         "defaults.format" => config.defaults.format,
         "defaults.output_dir" => config.defaults.output_dir,
+        "defaults.output_template" => config.defaults.output_template,
         "defaults.display" => config.defaults.display.map(|v| v.to_string()),
+        "defaults.poll_interval" => config.defaults.poll_interval,
+        "defaults.retries" => config.defaults.retries.map(|v| v.to_string()),
+        "defaults.strict_url" => config.defaults.strict_url.map(|v| v.to_string()),
+        "defaults.block_selectors" => Some(config.defaults.block_selectors.join("\n"))
+            .filter(|selectors| !selectors.is_empty()),
         "display.protocol" => config.display.protocol,
         "display.width" => config.display.width.map(|v| v.to_string()),
         "display.height" => config.display.height.map(|v| v.to_string()),
+        "display.max_width" => config.display.max_width.map(|v| v.to_string()),
         _ => {
             return Err(CliError::Other(format!("Unknown config key: {}", key)));
         }
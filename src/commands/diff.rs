@@ -0,0 +1,102 @@
+use crate::display::{create_spinner, file_hyperlink, icons, TerminalImage};
+use crate::error::{CliError, CliResult};
+use crate::image_diff::{self, DiffOutput};
+use crate::local_compose;
+use crate::utils::{encode_image, format_file_size, save_to_file};
+use clap::Args;
+use colored::Colorize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Baseline image
+    #[arg(required = true)]
+    pub baseline: PathBuf,
+
+    /// Image to compare with the baseline
+    #[arg(required = true)]
+    pub current: PathBuf,
+
+    /// Save the diff image (always PNG)
+    #[arg(short, long, id = "output", value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Diff image to produce: side-by-side, overlay, heatmap, changed-only
+    #[arg(long, value_name = "KIND", default_value = "side-by-side")]
+    pub diff_output: String,
+
+    /// Fail if more than this percentage of pixels differ
+    #[arg(long, value_name = "PERCENT")]
+    pub max_diff: Option<f64>,
+
+    /// Don't display in terminal
+    #[arg(long)]
+    pub no_display: bool,
+}
+
+/// Compare two local images and render where they differ
+pub fn execute(args: DiffArgs) -> CliResult<()> {
+    let kind = DiffOutput::parse(&args.diff_output)?;
+
+    let spinner = create_spinner("Comparing images...");
+    let images = local_compose::decode_images(&[args.baseline.clone(), args.current.clone()], None)?;
+    let (baseline, current) = (images[0].to_rgba8(), images[1].to_rgba8());
+    let diff = image_diff::render(&baseline, &current, kind);
+    let (changed, total) = (diff.changed, diff.total);
+    let percent = diff.changed_ratio() * 100.0;
+    let image_bytes = encode_image(
+        &image::DynamicImage::ImageRgba8(diff.image),
+        image::ImageFormat::Png,
+        None,
+    )?;
+    spinner.finish_and_clear();
+
+    if changed == 0 {
+        println!("{} Images match", icons::success().green());
+    } else {
+        println!(
+            "{} {:.2}% of pixels differ ({} of {})",
+            icons::warning().yellow(),
+            percent,
+            changed,
+            total
+        );
+    }
+    if baseline.dimensions() != current.dimensions() {
+        println!(
+            "  {}",
+            format!(
+                "Sizes differ ({}x{} vs {}x{}); aligned at the top-left corner",
+                baseline.width(),
+                baseline.height(),
+                current.width(),
+                current.height()
+            )
+            .dimmed()
+        );
+    }
+
+    if let Some(ref output) = args.output {
+        save_to_file(output, &image_bytes)?;
+        println!(
+            "  Saved to: {} ({})",
+            file_hyperlink(output, output.display().to_string().cyan()),
+            format_file_size(image_bytes.len() as u64)
+        );
+    }
+
+    if !args.no_display && changed > 0 {
+        println!();
+        let display = TerminalImage::new();
+        display.display_bytes(&image_bytes)?;
+        println!();
+    }
+
+    match args.max_diff {
+        Some(max_diff) if percent > max_diff => Err(CliError::Other(format!(
+            "{:.2}% of pixels differ (allowed {}%)",
+            percent, max_diff
+        ))),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,183 @@
+use crate::config::Config;
+use crate::context::Context;
+use crate::display::{icons, TerminalImage};
+use crate::error::{CliError, CliResult};
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+        }
+    }
+}
+
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Run environment checks and print a pass/warn/fail checklist
+pub async fn execute(ctx: &Context) -> CliResult<()> {
+    let (config_check, config) = check_config();
+    let checks = vec![
+        config_check,
+        check_api_key(ctx).await,
+        check_output_dir(&config),
+        check_display(&config),
+        check_clipboard(),
+    ];
+
+    if ctx.json {
+        let json: Vec<_> = checks
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "check": c.name,
+                    "status": c.status.as_str(),
+                    "detail": c.detail,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json)
+                .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?
+        );
+    } else {
+        println!("{}", "Allscreenshots Doctor".bold().underline());
+        println!();
+        for check in &checks {
+            let icon = match check.status {
+                Status::Pass => icons::success().green(),
+                Status::Warn => icons::warning().yellow(),
+                Status::Fail => icons::failure().red(),
+            };
+            println!("  {} {:<14} {}", icon, check.name, check.detail.dimmed());
+        }
+        println!();
+    }
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if failed > 0 {
+        return Err(CliError::Other(format!(
+            "{} critical check{} failed",
+            failed,
+            if failed == 1 { "" } else { "s" }
+        )));
+    }
+
+    Ok(())
+}
+
+fn check_config() -> (Check, Config) {
+    let path = Config::config_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    match Config::load() {
+        Ok(config) if Config::config_path().is_some_and(|p| p.exists()) => {
+            (Check::new("Config file", Status::Pass, path), config)
+        }
+        Ok(config) => (
+            Check::new("Config file", Status::Pass, format!("{} (not created, using defaults)", path)),
+            config,
+        ),
+        Err(e) => (
+            Check::new("Config file", Status::Fail, format!("{}: {}", path, e)),
+            Config::default(),
+        ),
+    }
+}
+
+async fn check_api_key(ctx: &Context) -> Check {
+    let client = match ctx.client() {
+        Ok(client) => client,
+        Err(CliError::NoApiKey) => {
+            return Check::new(
+                "API key",
+                Status::Fail,
+                "not set (run: allscreenshots config add-authtoken <key>)",
+            )
+        }
+        Err(e) => return Check::new("API key", Status::Fail, e.to_string()),
+    };
+
+    // Quota is the cheapest authenticated call
+    match tokio::time::timeout(Duration::from_secs(10), client.get_quota()).await {
+        Ok(Ok(quota)) => Check::new("API key", Status::Pass, format!("valid ({} tier)", quota.tier)),
+        Ok(Err(e)) => Check::new("API key", Status::Fail, e.to_string()),
+        Err(_) => Check::new("API key", Status::Fail, "API did not respond within 10s"),
+    }
+}
+
+fn check_output_dir(config: &Config) -> Check {
+    let dir = config
+        .defaults
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| "./screenshots".to_string());
+    let path = Path::new(&dir);
+
+    if !path.exists() {
+        return Check::new("Output dir", Status::Warn, format!("{} (will be created on first save)", dir));
+    }
+
+    let probe = path.join(".allscreenshots-doctor");
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            Check::new("Output dir", Status::Pass, format!("{} (writable)", dir))
+        }
+        Err(e) => Check::new("Output dir", Status::Fail, format!("{} is not writable: {}", dir, e)),
+    }
+}
+
+fn check_display(config: &Config) -> Check {
+    let detected = TerminalImage::detect_protocol();
+    let configured = config.display.protocol.as_deref().unwrap_or("auto");
+
+    if detected == "blocks" {
+        Check::new(
+            "Image display",
+            Status::Warn,
+            format!("no graphics protocol detected, using block characters (config: {})", configured),
+        )
+    } else {
+        Check::new(
+            "Image display",
+            Status::Pass,
+            format!("{} (config: {})", detected, configured),
+        )
+    }
+}
+
+fn check_clipboard() -> Check {
+    match arboard::Clipboard::new() {
+        Ok(_) => Check::new("Clipboard", Status::Pass, "available"),
+        Err(e) => Check::new("Clipboard", Status::Warn, format!("unavailable: {}", e)),
+    }
+}
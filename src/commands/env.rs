@@ -0,0 +1,73 @@
+use crate::commands::completions::parse_shell;
+use crate::config::Config;
+use crate::context::Context;
+use crate::error::{CliError, CliResult};
+use clap::Args;
+use clap_complete::Shell;
+use colored::Colorize;
+
+#[derive(Args, Debug)]
+pub struct EnvArgs {
+    /// Shell syntax: bash, zsh, fish, powershell, elvish
+    #[arg(long, default_value = "bash")]
+    pub shell: String,
+
+    /// Print the real API key instead of a commented-out masked one
+    #[arg(long)]
+    pub reveal: bool,
+}
+
+/// Print shell lines that export the API key (and API base, if set)
+pub fn execute(args: EnvArgs, ctx: &Context) -> CliResult<()> {
+    let shell = parse_shell(&args.shell)?;
+    let api_key = ctx.api_key.as_deref().ok_or(CliError::NoApiKey)?;
+
+    if args.reveal {
+        println!("{}", export_line(shell, "ALLSCREENSHOTS_API_KEY", api_key));
+    } else {
+        // Commented out so eval-ing the output can't replace a working key with the mask
+        let masked = Config::mask_api_key(api_key);
+        println!("# {}", export_line(shell, "ALLSCREENSHOTS_API_KEY", &masked));
+    }
+    if let Some(ref base) = ctx.api_base {
+        println!("{}", export_line(shell, "ALLSCREENSHOTS_API_BASE", base));
+    }
+
+    if !args.reveal {
+        eprintln!(
+            "{}",
+            "API key is masked and commented out; pass --reveal to emit the real value".dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Format one variable assignment in the given shell's syntax
+fn export_line(shell: Shell, name: &str, value: &str) -> String {
+    match shell {
+        Shell::Fish => format!("set -gx {} {}", name, single_quote(value, "\\'")),
+        Shell::PowerShell => format!("$env:{} = {}", name, single_quote(value, "''")),
+        Shell::Elvish => format!("set-env {} {}", name, single_quote(value, "''")),
+        _ => format!("export {}={}", name, single_quote(value, "'\\''")),
+    }
+}
+
+/// Wrap in single quotes, replacing embedded quotes with `escaped`
+fn single_quote(value: &str, escaped: &str) -> String {
+    format!("'{}'", value.replace('\'', escaped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_line() {
+        assert_eq!(export_line(Shell::Bash, "KEY", "abc"), "export KEY='abc'");
+        assert_eq!(export_line(Shell::Zsh, "KEY", "a'b"), "export KEY='a'\\''b'");
+        assert_eq!(export_line(Shell::Fish, "KEY", "a'b"), "set -gx KEY 'a\\'b'");
+        assert_eq!(export_line(Shell::PowerShell, "KEY", "a'b"), "$env:KEY = 'a''b'");
+        assert_eq!(export_line(Shell::Elvish, "KEY", "abc"), "set-env KEY 'abc'");
+    }
+}
@@ -1,6 +1,8 @@
+use crate::context::Context;
 use crate::display::{create_spinner, TerminalImage};
 use crate::error::{CliError, CliResult};
-use allscreenshots_sdk::{AllscreenshotsClient, JobStatus};
+use crate::retry::retry_async;
+use allscreenshots_sdk::JobStatus;
 use clap::Args;
 use colored::Colorize;
 use std::path::PathBuf;
@@ -20,7 +22,7 @@ pub struct GalleryArgs {
     pub size: String,
 }
 
-pub async fn execute(args: GalleryArgs, api_key: Option<String>) -> CliResult<()> {
+pub async fn execute(args: GalleryArgs, ctx: &Context) -> CliResult<()> {
     let (width, height) = match args.size.as_str() {
         "medium" => (60, 15),
         _ => (40, 10), // small
@@ -29,7 +31,7 @@ pub async fn execute(args: GalleryArgs, api_key: Option<String>) -> CliResult<()
     if let Some(ref dir) = args.dir {
         display_local_gallery(dir, args.limit, width, height)
     } else {
-        display_api_gallery(api_key, args.limit, width, height).await
+        display_api_gallery(ctx, args.limit, width, height).await
     }
 }
 
@@ -108,16 +110,20 @@ fn display_local_gallery(dir: &PathBuf, limit: usize, width: u32, height: u32) -
 
 /// Display images from recent API jobs
 async fn display_api_gallery(
-    api_key: Option<String>,
+    ctx: &Context,
     limit: usize,
     width: u32,
     height: u32,
 ) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+    let client = ctx.client()?;
+    let policy = ctx.retry_policy();
 
     let spinner = create_spinner("Fetching recent screenshots...");
-    let jobs = client.list_jobs().await.map_err(CliError::Sdk)?;
+    let jobs = retry_async(
+        || async { client.list_jobs().await.map_err(CliError::Sdk) },
+        &policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
     println!("{}", "Gallery".bold().underline());
@@ -141,7 +147,12 @@ async fn display_api_gallery(
     for job in &completed_jobs {
         // Download the image
         let spinner = create_spinner(&format!("Loading {}...", job.id));
-        match client.get_job_result(&job.id).await {
+        let result = retry_async(
+            || async { client.get_job_result(&job.id).await.map_err(CliError::Sdk) },
+            &policy,
+        )
+        .await;
+        match result {
             Ok(bytes) => {
                 spinner.finish_and_clear();
 
@@ -1,7 +1,11 @@
-use crate::display::{create_spinner, TerminalImage};
+use crate::context::Context;
+use crate::display::{
+    create_progress_bar, create_spinner, file_hyperlink, hyperlink, icons, Table, TerminalImage,
+};
 use crate::error::{CliError, CliResult};
-use crate::utils::save_to_file;
-use allscreenshots_sdk::{AllscreenshotsClient, JobStatus};
+use crate::retry::{is_connect_failure, retry_async, RetryPolicy};
+use crate::utils::{csv_field, parse_duration, parse_since, save_to_file};
+use allscreenshots_sdk::{AllscreenshotsClient, JobResponse, JobStatus};
 use clap::{Args, Subcommand};
 use colored::Colorize;
 use std::path::PathBuf;
@@ -15,12 +19,16 @@ pub struct JobsCommand {
 #[derive(Subcommand, Debug)]
 pub enum JobsSubcommand {
     /// List recent jobs
-    List,
+    List(ListArgs),
 
     /// Get job status
     Get {
         /// Job ID
         id: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Cancel a job
@@ -29,6 +37,12 @@ pub enum JobsSubcommand {
         id: String,
     },
 
+    /// Cancel stale jobs matching the given filters
+    Prune(PruneArgs),
+
+    /// Export every job with all its fields as CSV or JSON
+    Export(ExportArgs),
+
     /// Download job result
     Result {
         /// Job ID
@@ -41,30 +55,116 @@ pub enum JobsSubcommand {
         /// Display in terminal
         #[arg(long)]
         display: bool,
+
+        /// Print result metadata (path, size) as JSON
+        #[arg(long)]
+        json: bool,
     },
 }
 
-pub async fn execute(cmd: JobsCommand, api_key: Option<String>) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    /// Only jobs with this status: queued, processing, completed, failed, cancelled
+    #[arg(long)]
+    pub status: Option<String>,
+
+    /// Keep redrawing the list until Ctrl+C
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Refresh interval for --watch (e.g., "2s", "1m")
+    #[arg(long, default_value = "2s", requires = "watch")]
+    pub interval: String,
+}
+
+#[derive(Args, Debug)]
+pub struct PruneArgs {
+    /// Only jobs created longer ago than this (e.g., "1h", "7d")
+    #[arg(long, value_name = "DURATION")]
+    pub older_than: Option<String>,
+
+    /// Only jobs with this status: queued, processing, completed, failed, cancelled
+    #[arg(long)]
+    pub status: Option<String>,
+
+    /// Don't ask for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Export format: csv, json
+    #[arg(long, default_value = "csv")]
+    pub format: String,
+
+    /// Write to a file instead of stdout
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Only jobs with this status: queued, processing, completed, failed, cancelled
+    #[arg(long)]
+    pub status: Option<String>,
+
+    /// Only jobs created since a date, timestamp or duration ago (e.g. "2024-05-01", "7d")
+    #[arg(long, value_name = "WHEN")]
+    pub since: Option<String>,
+}
+
+pub async fn execute(cmd: JobsCommand, ctx: &Context) -> CliResult<()> {
+    let client = ctx.client()?;
+    let policy = ctx.retry_policy();
 
     match cmd.command {
-        JobsSubcommand::List => list_jobs(&client).await,
-        JobsSubcommand::Get { id } => get_job(&client, &id).await,
-        JobsSubcommand::Cancel { id } => cancel_job(&client, &id).await,
-        JobsSubcommand::Result { id, output, display } => {
-            get_result(&client, &id, output, display).await
-        }
+        JobsSubcommand::List(args) => list_jobs(&client, &policy, args).await,
+        JobsSubcommand::Get { id, json } => get_job(&client, &policy, &id, json || ctx.json).await,
+        JobsSubcommand::Cancel { id } => cancel_job(&client, &policy, &id).await,
+        JobsSubcommand::Prune(args) => prune_jobs(&client, &policy, args).await,
+        JobsSubcommand::Export(args) => export_jobs(&client, &policy, args).await,
+        JobsSubcommand::Result {
+            id,
+            output,
+            display,
+            json,
+        } => get_result(&client, &policy, &id, output, display, json || ctx.json).await,
     }
 }
 
+async fn fetch_jobs(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+) -> CliResult<Vec<JobResponse>> {
+    retry_async(
+        || async { client.list_jobs().await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await
+}
+
+/// Cancelling isn't idempotent, so only retry when the request never reached the API
+async fn cancel(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    id: &str,
+) -> CliResult<JobResponse> {
+    let policy = RetryPolicy {
+        retry_if: is_connect_failure,
+        ..policy.clone()
+    };
+    retry_async(
+        || async { client.cancel_job(id).await.map_err(CliError::Sdk) },
+        &policy,
+    )
+    .await
+}
+
 fn status_icon(status: &JobStatus) -> colored::ColoredString {
     match status {
-        JobStatus::Completed => "✓".green(),
-        JobStatus::Failed => "✗".red(),
-        JobStatus::Cancelled => "⊘".yellow(),
-        JobStatus::Processing => "⟳".cyan(),
-        JobStatus::Queued => "○".dimmed(),
+        JobStatus::Completed => icons::success().green(),
+        JobStatus::Failed => icons::failure().red(),
+        JobStatus::Cancelled => icons::cancelled().yellow(),
+        JobStatus::Processing => icons::processing().cyan(),
+        JobStatus::Queued => icons::queued().dimmed(),
     }
 }
 
@@ -78,63 +178,139 @@ fn status_color(status: &JobStatus) -> &'static str {
     }
 }
 
-async fn list_jobs(client: &AllscreenshotsClient) -> CliResult<()> {
+async fn list_jobs(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    args: ListArgs,
+) -> CliResult<()> {
+    let status = args.status.as_deref().map(parse_status).transpose()?;
+
+    if args.watch {
+        return watch_jobs(client, policy, status, parse_duration(&args.interval)?).await;
+    }
+
     let spinner = create_spinner("Fetching jobs...");
-    let jobs = client.list_jobs().await.map_err(CliError::Sdk)?;
+    let jobs = fetch_jobs(client, policy).await?;
     spinner.finish_and_clear();
 
-    if jobs.is_empty() {
-        println!("{}", "No jobs found.".dimmed());
-        return Ok(());
+    for line in render_jobs(&jobs, status) {
+        println!("{}", line);
     }
 
-    println!("{}", "Recent Jobs".bold().underline());
-    println!();
-
-    for job in jobs {
-        let icon = status_icon(&job.status);
-        let status_str = format!("{:?}", job.status);
+    Ok(())
+}
 
-        println!(
-            "{} {} ({})",
-            icon,
-            job.id.cyan(),
-            status_str.bold()
+/// Redraw the job list in place every `interval` until Ctrl+C
+async fn watch_jobs(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    status: Option<JobStatus>,
+    interval: std::time::Duration,
+) -> CliResult<()> {
+    let term = console::Term::stdout();
+    let mut drawn = 0;
+    let mut ctrl_c = std::pin::pin!(tokio::signal::ctrl_c());
+
+    loop {
+        let mut lines = match fetch_jobs(client, policy).await {
+            Ok(jobs) => render_jobs(&jobs, status),
+            // Keep watching through transient errors
+            Err(e) => vec![format!("{} {}", icons::failure().red(), e)],
+        };
+        lines.push(
+            format!(
+                "Updated {} - refreshing every {} (Ctrl+C to stop)",
+                chrono::Local::now().format("%H:%M:%S"),
+                humantime::format_duration(interval)
+            )
+            .dimmed()
+            .to_string(),
         );
 
-        if let Some(ref url) = job.url {
-            println!("    URL: {}", url.dimmed());
+        // Overwrite the previous frame; clear the screen if it scrolled past the top
+        let height = term.size().0 as usize;
+        if drawn >= height {
+            let _ = term.clear_screen();
+        } else {
+            let _ = term.clear_last_lines(drawn);
         }
-
-        if let Some(ref created) = job.created_at {
-            println!("    Created: {}", created.dimmed());
+        for line in &lines {
+            println!("{}", line);
         }
+        drawn = lines.len();
 
-        if let Some(ref completed) = job.completed_at {
-            println!("    Completed: {}", completed.dimmed());
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = &mut ctrl_c => break,
         }
+    }
+
+    Ok(())
+}
+
+/// Format jobs (optionally only one status) as printable lines
+fn render_jobs(jobs: &[JobResponse], status: Option<JobStatus>) -> Vec<String> {
+    let jobs: Vec<&JobResponse> = jobs
+        .iter()
+        .filter(|job| status.map_or(true, |s| job.status == s))
+        .collect();
+
+    if jobs.is_empty() {
+        return vec!["No jobs found.".dimmed().to_string()];
+    }
+
+    let mut table = Table::new(&["", "ID", "Status", "URL", "Created"]);
+    let mut errors = Vec::new();
+
+    for job in jobs {
+        let color = status_color(&job.status);
+        table.add_row(vec![
+            status_icon(&job.status).to_string(),
+            job.id.cyan().to_string(),
+            format!("{:?}", job.status).color(color).to_string(),
+            job.url.clone().unwrap_or_default(),
+            job.created_at.as_deref().unwrap_or("-").dimmed().to_string(),
+        ]);
 
         if job.status == JobStatus::Failed {
             if let Some(ref error) = job.error_message {
-                println!("    Error: {}", error.red());
+                errors.push(format!("  {} {}", job.id.dimmed(), error.red()));
             }
         }
+    }
 
-        if let Some(ref result_url) = job.result_url {
-            println!("    Result: {}", result_url.dimmed());
-        }
-
-        println!();
+    let mut lines = vec!["Recent Jobs".bold().underline().to_string(), String::new()];
+    lines.extend(table.render());
+    if !errors.is_empty() {
+        lines.push(String::new());
+        lines.push("Errors".bold().to_string());
+        lines.extend(errors);
     }
 
-    Ok(())
+    lines
 }
 
-async fn get_job(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
+async fn get_job(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    id: &str,
+    json: bool,
+) -> CliResult<()> {
     let spinner = create_spinner("Fetching job...");
-    let job = client.get_job(id).await.map_err(CliError::Sdk)?;
+    let job = retry_async(
+        || async { client.get_job(id).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
+    if json {
+        let json = serde_json::to_string_pretty(&job)
+            .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
     let color = status_color(&job.status);
     let status_str = format!("{:?}", job.status);
 
@@ -164,7 +340,7 @@ async fn get_job(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
     }
 
     if let Some(ref result_url) = job.result_url {
-        println!("  Result URL: {}", result_url.cyan());
+        println!("  Result URL: {}", hyperlink(result_url, result_url.cyan()));
     }
 
     if let Some(ref error_code) = job.error_code {
@@ -187,13 +363,17 @@ async fn get_job(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
     Ok(())
 }
 
-async fn cancel_job(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
+async fn cancel_job(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    id: &str,
+) -> CliResult<()> {
     let spinner = create_spinner("Cancelling job...");
-    let job = client.cancel_job(id).await.map_err(CliError::Sdk)?;
+    let job = cancel(client, policy, id).await?;
     spinner.finish_and_clear();
 
     if job.status == JobStatus::Cancelled {
-        println!("{} Job {} cancelled", "✓".green(), id);
+        println!("{} Job {} cancelled", icons::success().green(), id);
     } else {
         println!(
             "{} Job {} is now {:?} (may have completed before cancellation)",
@@ -206,15 +386,229 @@ async fn cancel_job(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
     Ok(())
 }
 
+fn parse_status(s: &str) -> CliResult<JobStatus> {
+    match s.to_lowercase().as_str() {
+        "queued" => Ok(JobStatus::Queued),
+        "processing" => Ok(JobStatus::Processing),
+        "completed" => Ok(JobStatus::Completed),
+        "failed" => Ok(JobStatus::Failed),
+        "cancelled" | "canceled" => Ok(JobStatus::Cancelled),
+        _ => Err(CliError::Other(format!(
+            "Invalid status '{}'. Use: queued, processing, completed, failed, or cancelled",
+            s
+        ))),
+    }
+}
+
+/// Check whether a job was created before `cutoff`; jobs without a
+/// readable timestamp never match
+fn created_before(job: &JobResponse, cutoff: chrono::DateTime<chrono::Utc>) -> bool {
+    job.created_at
+        .as_deref()
+        .and_then(|created| chrono::DateTime::parse_from_rfc3339(created).ok())
+        .is_some_and(|created| created < cutoff)
+}
+
+async fn prune_jobs(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    args: PruneArgs,
+) -> CliResult<()> {
+    let status = args.status.as_deref().map(parse_status).transpose()?;
+    let cutoff = match args.older_than {
+        Some(ref age) => {
+            let age = chrono::Duration::from_std(parse_duration(age)?)
+                .map_err(|e| CliError::Other(format!("Invalid duration: {}", e)))?;
+            Some(chrono::Utc::now() - age)
+        }
+        None => None,
+    };
+
+    let spinner = create_spinner("Fetching jobs...");
+    let jobs = fetch_jobs(client, policy).await?;
+    spinner.finish_and_clear();
+
+    let matching: Vec<JobResponse> = jobs
+        .into_iter()
+        .filter(|job| status.map_or(true, |s| job.status == s))
+        .filter(|job| cutoff.map_or(true, |cutoff| created_before(job, cutoff)))
+        .collect();
+
+    // The API has no delete endpoint, so only unfinished jobs can be pruned (cancelled)
+    let (cancellable, finished): (Vec<_>, Vec<_>) =
+        matching.into_iter().partition(|job| !job.status.is_terminal());
+
+    if !finished.is_empty() {
+        println!(
+            "{} {} matching job(s) have already finished and can't be removed (the API has no delete endpoint)",
+            "Note:".yellow(),
+            finished.len()
+        );
+    }
+
+    if cancellable.is_empty() {
+        println!("{}", "No queued or processing jobs to prune.".dimmed());
+        return Ok(());
+    }
+
+    println!("{}", "Jobs to cancel".bold().underline());
+    println!();
+    for job in &cancellable {
+        println!(
+            "{} {} ({:?}) {}",
+            status_icon(&job.status),
+            job.id.cyan(),
+            job.status,
+            job.url.as_deref().unwrap_or_default().dimmed()
+        );
+    }
+    println!();
+
+    if !args.yes {
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!("Cancel {} job(s)?", cancellable.len()))
+            .default(false)
+            .interact()
+            .map_err(|e| {
+                CliError::Other(format!("Couldn't ask for confirmation ({}). Pass --yes to skip it", e))
+            })?;
+        if !confirmed {
+            println!("{}", "Aborted, no jobs were cancelled.".dimmed());
+            return Ok(());
+        }
+    }
+
+    let progress = create_progress_bar(cancellable.len() as u64, "Cancelling jobs");
+    let mut pruned = 0;
+    let mut failed = 0;
+
+    for job in &cancellable {
+        match cancel(client, policy, &job.id).await {
+            Ok(_) => pruned += 1,
+            Err(e) => {
+                progress.suspend(|| {
+                    eprintln!("  {} Failed to cancel {}: {}", icons::failure().red(), job.id, e)
+                });
+                failed += 1;
+            }
+        }
+        progress.inc(1);
+    }
+    progress.finish_and_clear();
+
+    println!("{} Pruned {} job(s)", icons::success().green(), pruned);
+    if failed > 0 {
+        println!("  {} {}", "Failed:".red(), failed);
+    }
+
+    Ok(())
+}
+
+async fn export_jobs(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    args: ExportArgs,
+) -> CliResult<()> {
+    let status = args.status.as_deref().map(parse_status).transpose()?;
+    let since = args.since.as_deref().map(parse_since).transpose()?;
+    let format = args.format.to_lowercase();
+    if format != "csv" && format != "json" {
+        return Err(CliError::Other(format!(
+            "Invalid export format '{}'. Use: csv or json",
+            args.format
+        )));
+    }
+
+    let spinner = create_spinner("Fetching jobs...");
+    let jobs = fetch_jobs(client, policy).await?;
+    spinner.finish_and_clear();
+
+    let jobs: Vec<JobResponse> = jobs
+        .into_iter()
+        .filter(|job| status.map_or(true, |s| job.status == s))
+        .filter(|job| since.map_or(true, |since| created_since(job, since)))
+        .collect();
+
+    let report = if format == "json" {
+        let json = serde_json::to_string_pretty(&jobs)
+            .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
+        format!("{}\n", json)
+    } else {
+        jobs_csv(&jobs)
+    };
+
+    match args.output {
+        Some(ref path) => {
+            save_to_file(path, report.as_bytes())?;
+            println!(
+                "{} Exported {} job(s) to {}",
+                icons::success().green(),
+                jobs.len(),
+                file_hyperlink(path, path.display().to_string().cyan())
+            );
+        }
+        None => print!("{}", report),
+    }
+
+    Ok(())
+}
+
+/// Check whether a job was created at or after `since`; jobs without a
+/// readable timestamp never match
+fn created_since(job: &JobResponse, since: chrono::DateTime<chrono::Utc>) -> bool {
+    job.created_at
+        .as_deref()
+        .and_then(|created| chrono::DateTime::parse_from_rfc3339(created).ok())
+        .is_some_and(|created| created >= since)
+}
+
+/// One row per job with every field; metadata is kept as compact JSON
+fn jobs_csv(jobs: &[JobResponse]) -> String {
+    let mut out = String::from(
+        "id,status,url,created_at,started_at,completed_at,expires_at,error_code,error_message,result_url,metadata\n",
+    );
+    for job in jobs {
+        let status = format!("{:?}", job.status).to_lowercase();
+        let metadata = job
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.to_string())
+            .unwrap_or_default();
+        let fields = [
+            job.id.as_str(),
+            status.as_str(),
+            job.url.as_deref().unwrap_or_default(),
+            job.created_at.as_deref().unwrap_or_default(),
+            job.started_at.as_deref().unwrap_or_default(),
+            job.completed_at.as_deref().unwrap_or_default(),
+            job.expires_at.as_deref().unwrap_or_default(),
+            job.error_code.as_deref().unwrap_or_default(),
+            job.error_message.as_deref().unwrap_or_default(),
+            job.result_url.as_deref().unwrap_or_default(),
+            metadata.as_str(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
 async fn get_result(
     client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
     id: &str,
     output: Option<PathBuf>,
     display: bool,
+    json: bool,
 ) -> CliResult<()> {
     // First check job status
     let spinner = create_spinner("Checking job status...");
-    let job = client.get_job(id).await.map_err(CliError::Sdk)?;
+    let job = retry_async(
+        || async { client.get_job(id).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
 
     if job.status != JobStatus::Completed {
         spinner.finish_and_clear();
@@ -225,19 +619,40 @@ async fn get_result(
     }
 
     spinner.set_message("Downloading result...");
-    let image_bytes = client.get_job_result(id).await.map_err(CliError::Sdk)?;
+    let image_bytes = retry_async(
+        || async { client.get_job_result(id).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
+    if json {
+        if let Some(ref path) = output {
+            save_to_file(path, &image_bytes)?;
+        }
+
+        let summary = serde_json::json!({
+            "id": job.id,
+            "status": job.status,
+            "path": output.as_ref().map(|p| p.display().to_string()),
+            "size": image_bytes.len(),
+        });
+        let json = serde_json::to_string_pretty(&summary)
+            .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
     println!(
         "{} Downloaded {}",
-        "✓".green(),
+        icons::success().green(),
         crate::utils::format_file_size(image_bytes.len() as u64)
     );
 
     // Save to file
     if let Some(ref path) = output {
         save_to_file(path, &image_bytes)?;
-        println!("  Saved to: {}", path.display().to_string().cyan());
+        println!("  Saved to: {}", file_hyperlink(path, path.display().to_string().cyan()));
     }
 
     // Display in terminal
@@ -251,3 +666,49 @@ async fn get_result(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: &str, status: JobStatus, created_at: Option<&str>) -> JobResponse {
+        JobResponse {
+            id: id.to_string(),
+            status,
+            url: Some("https://example.com".to_string()),
+            result_url: None,
+            error_code: None,
+            error_message: None,
+            created_at: created_at.map(str::to_string),
+            started_at: None,
+            completed_at: None,
+            expires_at: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_jobs_csv() {
+        let mut failed = job("job_2", JobStatus::Failed, Some("2024-05-01T10:00:00Z"));
+        failed.error_message = Some("Timeout, page never loaded".to_string());
+        failed.metadata = Some(serde_json::json!({"attempt": 2}));
+
+        let csv = jobs_csv(&[job("job_1", JobStatus::Completed, None), failed]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("id,status,url,"));
+        assert_eq!(lines[1], "job_1,completed,https://example.com,,,,,,,,");
+        assert_eq!(
+            lines[2],
+            "job_2,failed,https://example.com,2024-05-01T10:00:00Z,,,,,\"Timeout, page never loaded\",,\"{\"\"attempt\"\":2}\""
+        );
+    }
+
+    #[test]
+    fn test_created_since() {
+        let since = parse_since("2024-05-01").unwrap();
+        assert!(created_since(&job("a", JobStatus::Queued, Some("2024-05-02T00:00:00Z")), since));
+        assert!(!created_since(&job("b", JobStatus::Queued, Some("2024-04-30T23:59:59Z")), since));
+        assert!(!created_since(&job("c", JobStatus::Queued, None), since));
+    }
+}
@@ -0,0 +1,63 @@
+use crate::context::Context;
+use crate::error::{CliError, CliResult};
+use crate::metadata::read_metadata;
+use clap::Args;
+use colored::Colorize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct MetadataArgs {
+    /// Image file to inspect
+    #[arg(required = true)]
+    pub file: PathBuf,
+}
+
+pub async fn execute(args: MetadataArgs, ctx: &Context) -> CliResult<()> {
+    if !args.file.exists() {
+        return Err(CliError::FileNotFound(args.file.display().to_string()));
+    }
+
+    let bytes = std::fs::read(&args.file)
+        .map_err(|e| CliError::FileReadError(format!("{}: {}", args.file.display(), e)))?;
+
+    let meta = read_metadata(&bytes)?.ok_or_else(|| {
+        CliError::Other(format!(
+            "{} is not a PNG, JPEG or WebP image and can't carry metadata",
+            args.file.display()
+        ))
+    })?;
+
+    if ctx.json {
+        let json = serde_json::to_string_pretty(&meta)
+            .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    println!("{}", "Screenshot Metadata".bold().underline());
+    println!();
+
+    if meta == Default::default() {
+        println!("{}", "No capture metadata found.".dimmed());
+        println!(
+            "\n{}",
+            "Tip: use `allscreenshots capture <url> -o <file> --embed-metadata` to embed it".dimmed()
+        );
+        return Ok(());
+    }
+
+    let fields = [
+        ("URL", &meta.url),
+        ("Captured", &meta.captured_at),
+        ("Device", &meta.device),
+        ("Software", &meta.software),
+    ];
+
+    for (label, value) in fields {
+        if let Some(value) = value {
+            println!("  {}: {}", label, value);
+        }
+    }
+
+    Ok(())
+}
@@ -4,8 +4,16 @@ pub mod capture;
 pub mod compose;
 pub mod completions;
 pub mod config;
+pub mod diff;
+pub mod doctor;
+pub mod env;
 pub mod gallery;
 pub mod jobs;
+pub mod metadata;
+pub mod pdf;
 pub mod schedule;
+#[cfg(feature = "self-update")]
+pub mod update;
 pub mod usage;
+pub mod version;
 pub mod watch;
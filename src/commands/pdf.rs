@@ -0,0 +1,118 @@
+use crate::context::Context;
+use crate::display::{create_spinner, file_hyperlink, icons};
+use crate::error::{CliError, CliResult};
+use crate::pdf_merge::{is_pdf, merge_pdfs};
+use crate::retry::retry_async;
+use crate::utils::{expand_device_alias, normalize_url, save_to_file};
+use allscreenshots_sdk::{ImageFormat, ScreenshotRequest};
+use clap::Args;
+use colored::Colorize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct PdfArgs {
+    /// URLs to capture, in page order
+    #[arg(required = true, value_name = "URL")]
+    pub urls: Vec<String>,
+
+    /// Merged PDF path
+    #[arg(short, long)]
+    pub output: PathBuf,
+
+    /// Device preset
+    #[arg(short, long)]
+    pub device: Option<String>,
+
+    /// Capture full pages
+    #[arg(long)]
+    pub full_page: bool,
+
+    /// Exit with an error if any URL fails instead of merging the rest
+    #[arg(long)]
+    pub fail_on_any: bool,
+}
+
+/// Capture each URL as a PDF and merge them into one document
+pub async fn execute(args: PdfArgs, ctx: &Context) -> CliResult<()> {
+    let urls: Vec<String> = args
+        .urls
+        .iter()
+        .map(|u| normalize_url(u))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let client = ctx.client()?;
+    let policy = ctx.retry_policy();
+
+    println!("{} {} URLs", "PDF capture:".cyan().bold(), urls.len());
+
+    let mut documents = Vec::new();
+    let mut failed = Vec::new();
+
+    for url in &urls {
+        let mut builder = ScreenshotRequest::builder()
+            .url(url)
+            .format(ImageFormat::Pdf);
+        if let Some(ref device) = args.device {
+            builder = builder.device(expand_device_alias(device));
+        }
+        if args.full_page {
+            builder = builder.full_page(true);
+        }
+        if let Some(css) = ctx.block_css() {
+            builder = builder.custom_css(css);
+        }
+        let request = builder.build().map_err(|e| CliError::Other(e.to_string()))?;
+
+        let spinner = create_spinner(&format!("Capturing {}...", url));
+        let result = retry_async(
+            || async { client.screenshot(&request).await.map_err(CliError::Sdk) },
+            &policy,
+        )
+        .await;
+        spinner.finish_and_clear();
+
+        match result {
+            Ok(bytes) if is_pdf(&bytes) => {
+                println!("  {} {}", icons::success().green(), url);
+                documents.push(bytes);
+            }
+            Ok(_) => {
+                eprintln!("  {} {} - response is not a PDF", icons::failure().red(), url);
+                failed.push(url);
+            }
+            Err(e) => {
+                eprintln!("  {} {} - {}", icons::failure().red(), url, e);
+                failed.push(url);
+            }
+        }
+    }
+
+    if documents.is_empty() {
+        return Err(CliError::Other("All captures failed, nothing to merge".to_string()));
+    }
+
+    if args.fail_on_any && !failed.is_empty() {
+        return Err(CliError::Other(format!(
+            "{} of {} captures failed",
+            failed.len(),
+            urls.len()
+        )));
+    }
+
+    let merged = merge_pdfs(&documents)?;
+    save_to_file(&args.output, &merged)?;
+
+    println!();
+    println!("{}", "PDF saved!".green().bold());
+    println!("  Pages from: {} of {} URLs", documents.len(), urls.len());
+    println!(
+        "  File size: {}",
+        crate::utils::format_file_size(merged.len() as u64)
+    );
+    println!(
+        "  Saved to: {}",
+        file_hyperlink(&args.output, args.output.display().to_string().cyan())
+    );
+
+    Ok(())
+}
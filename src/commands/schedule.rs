@@ -1,7 +1,11 @@
-use crate::display::create_spinner;
+use crate::context::Context;
+use crate::display::{create_spinner, icons, Table};
 use crate::error::{CliError, CliResult};
-use crate::utils::normalize_url;
-use allscreenshots_sdk::{AllscreenshotsClient, CreateScheduleRequest, UpdateScheduleRequest};
+use crate::retry::{is_connect_failure, retry_async, RetryPolicy};
+use crate::utils::{normalize_url, parse_since, preview_cron};
+use allscreenshots_sdk::{
+    AllscreenshotsClient, CreateScheduleRequest, ScheduleExecutionResponse, UpdateScheduleRequest,
+};
 use clap::{Args, Subcommand};
 use colored::Colorize;
 
@@ -57,9 +61,17 @@ pub enum ScheduleSubcommand {
         /// Schedule ID
         id: String,
 
-        /// Maximum number of entries
+        /// Maximum number of entries to fetch (filters apply to these)
         #[arg(long, default_value = "10")]
         limit: Option<i32>,
+
+        /// Only executions since a date, timestamp or duration ago (e.g. "2024-05-01", "7d")
+        #[arg(long, value_name = "WHEN")]
+        since: Option<String>,
+
+        /// Only executions with this status: completed, failed
+        #[arg(long)]
+        status: Option<String>,
     },
 }
 
@@ -92,6 +104,10 @@ pub struct CreateScheduleArgs {
     /// Webhook URL for notifications
     #[arg(long)]
     pub webhook_url: Option<String>,
+
+    /// Validate and preview the schedule without creating it
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Args, Debug)]
@@ -120,26 +136,45 @@ pub struct UpdateScheduleArgs {
     pub retention_days: Option<i32>,
 }
 
-pub async fn execute(cmd: ScheduleCommand, api_key: Option<String>) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+pub async fn execute(cmd: ScheduleCommand, ctx: &Context) -> CliResult<()> {
+    // Previews are local and don't need an API key
+    if let ScheduleSubcommand::Create(ref args) = cmd.command {
+        if args.dry_run {
+            return preview_schedule(args);
+        }
+    }
+
+    let client = ctx.client()?;
+    let policy = ctx.retry_policy();
 
     match cmd.command {
-        ScheduleSubcommand::List => list_schedules(&client).await,
-        ScheduleSubcommand::Create(args) => create_schedule(&client, args).await,
-        ScheduleSubcommand::Get { id } => get_schedule(&client, &id).await,
-        ScheduleSubcommand::Update(args) => update_schedule(&client, args).await,
-        ScheduleSubcommand::Delete { id } => delete_schedule(&client, &id).await,
-        ScheduleSubcommand::Pause { id } => pause_schedule(&client, &id).await,
-        ScheduleSubcommand::Resume { id } => resume_schedule(&client, &id).await,
-        ScheduleSubcommand::Trigger { id } => trigger_schedule(&client, &id).await,
-        ScheduleSubcommand::History { id, limit } => get_history(&client, &id, limit).await,
+        ScheduleSubcommand::List => list_schedules(&client, &policy).await,
+        ScheduleSubcommand::Create(args) => create_schedule(&client, &policy, args).await,
+        ScheduleSubcommand::Get { id } => get_schedule(&client, &policy, &id).await,
+        ScheduleSubcommand::Update(args) => update_schedule(&client, &policy, args).await,
+        ScheduleSubcommand::Delete { id } => delete_schedule(&client, &policy, &id).await,
+        ScheduleSubcommand::Pause { id } => pause_schedule(&client, &policy, &id).await,
+        ScheduleSubcommand::Resume { id } => resume_schedule(&client, &policy, &id).await,
+        ScheduleSubcommand::Trigger { id } => trigger_schedule(&client, &policy, &id).await,
+        ScheduleSubcommand::History {
+            id,
+            limit,
+            since,
+            status,
+        } => {
+            let filter = HistoryFilter::parse(since.as_deref(), status.as_deref())?;
+            get_history(&client, &policy, &id, limit, &filter, ctx.json).await
+        }
     }
 }
 
-async fn list_schedules(client: &AllscreenshotsClient) -> CliResult<()> {
+async fn list_schedules(client: &AllscreenshotsClient, policy: &RetryPolicy) -> CliResult<()> {
     let spinner = create_spinner("Fetching schedules...");
-    let schedules = client.list_schedules().await.map_err(CliError::Sdk)?;
+    let schedules = retry_async(
+        || async { client.list_schedules().await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
     if schedules.schedules.is_empty() {
@@ -150,6 +185,9 @@ async fn list_schedules(client: &AllscreenshotsClient) -> CliResult<()> {
     println!("{}", "Schedules".bold().underline());
     println!();
 
+    let mut table = Table::new(&["Name", "ID", "Status", "Schedule", "Description", "Next run", "Runs", "URL"])
+        .align_right(6);
+
     for schedule in schedules.schedules {
         let status_color = match schedule.status.as_str() {
             "ACTIVE" => "green",
@@ -157,38 +195,50 @@ async fn list_schedules(client: &AllscreenshotsClient) -> CliResult<()> {
             _ => "white",
         };
 
-        println!(
-            "{} {} ({})",
-            "•".color(status_color),
-            schedule.name.bold(),
-            schedule.id.dimmed()
-        );
-        println!("    URL: {}", schedule.url);
         let tz = schedule.timezone.as_deref().unwrap_or("UTC");
-        println!("    Schedule: {} ({})", schedule.schedule, tz);
-        if let Some(ref desc) = schedule.schedule_description {
-            println!("    Description: {}", desc.dimmed());
-        }
-        println!("    Status: {}", schedule.status.color(status_color));
-        if let Some(ref next) = schedule.next_execution_at {
-            println!("    Next run: {}", next.cyan());
-        }
         let exec_count = schedule.execution_count.unwrap_or(0);
         let success = schedule.success_count.unwrap_or(0);
         let failed = schedule.failure_count.unwrap_or(0);
-        println!(
-            "    Executions: {} total ({} success, {} failed)",
-            exec_count,
-            success.to_string().green(),
-            failed.to_string().red()
-        );
-        println!();
+
+        table.add_row(vec![
+            schedule.name.bold().to_string(),
+            schedule.id.dimmed().to_string(),
+            schedule.status.color(status_color).to_string(),
+            format!("{} ({})", schedule.schedule, tz),
+            schedule
+                .schedule_description
+                .as_deref()
+                .unwrap_or("-")
+                .dimmed()
+                .to_string(),
+            schedule
+                .next_execution_at
+                .as_deref()
+                .unwrap_or("-")
+                .cyan()
+                .to_string(),
+            format!(
+                "{} ({}/{})",
+                exec_count,
+                success.to_string().green(),
+                failed.to_string().red()
+            ),
+            schedule.url,
+        ]);
     }
 
+    table.print();
+    println!();
+    println!("{}", "Runs: total (success/failed)".dimmed());
+
     Ok(())
 }
 
-async fn create_schedule(client: &AllscreenshotsClient, args: CreateScheduleArgs) -> CliResult<()> {
+async fn create_schedule(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    args: CreateScheduleArgs,
+) -> CliResult<()> {
     let url = normalize_url(&args.url)?;
 
     let mut request = CreateScheduleRequest::new(&args.name, &url, &args.cron);
@@ -205,11 +255,17 @@ async fn create_schedule(client: &AllscreenshotsClient, args: CreateScheduleArgs
         request.webhook_url = Some(webhook.clone());
     }
 
+    // A repeated create would add a second schedule
+    let policy = RetryPolicy {
+        retry_if: is_connect_failure,
+        ..policy.clone()
+    };
     let spinner = create_spinner("Creating schedule...");
-    let schedule = client
-        .create_schedule(&request)
-        .await
-        .map_err(CliError::Sdk)?;
+    let schedule = retry_async(
+        || async { client.create_schedule(&request).await.map_err(CliError::Sdk) },
+        &policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
     println!("{}", "Schedule created!".green().bold());
@@ -224,9 +280,53 @@ async fn create_schedule(client: &AllscreenshotsClient, args: CreateScheduleArgs
     Ok(())
 }
 
-async fn get_schedule(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
+/// Number of upcoming runs shown by `create --dry-run`
+const PREVIEW_RUNS: usize = 5;
+
+fn preview_schedule(args: &CreateScheduleArgs) -> CliResult<()> {
+    let url = normalize_url(&args.url)?;
+    let (description, runs) = preview_cron(&args.cron, args.timezone.as_deref(), PREVIEW_RUNS)?;
+
+    println!("{}", "Schedule preview (not created)".bold().underline());
+    println!();
+    println!("  Name: {}", args.name.bold());
+    println!("  URL: {}", url);
+    println!("  Schedule: {} ({})", args.cron, description.dimmed());
+    println!("  Timezone: {}", args.timezone.as_deref().unwrap_or("UTC"));
+    if let Some(ref device) = args.device {
+        println!("  Device: {}", device);
+    }
+    if let Some(days) = args.retention_days {
+        println!("  Retention: {} days", days);
+    }
+    if let Some(ref webhook) = args.webhook_url {
+        println!("  Webhook: {}", webhook);
+    }
+
+    println!("\n{}", "Next runs".bold());
+    for run in runs {
+        println!("  {} {}", icons::bullet().dimmed(), run.cyan());
+    }
+
+    println!(
+        "\n{}",
+        "Run again without --dry-run to create this schedule".dimmed()
+    );
+
+    Ok(())
+}
+
+async fn get_schedule(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    id: &str,
+) -> CliResult<()> {
     let spinner = create_spinner("Fetching schedule...");
-    let schedule = client.get_schedule(id).await.map_err(CliError::Sdk)?;
+    let schedule = retry_async(
+        || async { client.get_schedule(id).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
     println!("{}", "Schedule Details".bold().underline());
@@ -263,7 +363,11 @@ async fn get_schedule(client: &AllscreenshotsClient, id: &str) -> CliResult<()>
     Ok(())
 }
 
-async fn update_schedule(client: &AllscreenshotsClient, args: UpdateScheduleArgs) -> CliResult<()> {
+async fn update_schedule(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    args: UpdateScheduleArgs,
+) -> CliResult<()> {
     let mut request = UpdateScheduleRequest::default();
 
     if let Some(ref name) = args.name {
@@ -283,10 +387,11 @@ async fn update_schedule(client: &AllscreenshotsClient, args: UpdateScheduleArgs
     }
 
     let spinner = create_spinner("Updating schedule...");
-    let schedule = client
-        .update_schedule(&args.id, &request)
-        .await
-        .map_err(CliError::Sdk)?;
+    let schedule = retry_async(
+        || async { client.update_schedule(&args.id, &request).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
     println!("{}", "Schedule updated!".green().bold());
@@ -296,38 +401,62 @@ async fn update_schedule(client: &AllscreenshotsClient, args: UpdateScheduleArgs
     Ok(())
 }
 
-async fn delete_schedule(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
+async fn delete_schedule(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    id: &str,
+) -> CliResult<()> {
     let spinner = create_spinner("Deleting schedule...");
-    client.delete_schedule(id).await.map_err(CliError::Sdk)?;
+    retry_async(
+        || async { client.delete_schedule(id).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
-    println!("{} Schedule {} deleted", "✓".green(), id);
+    println!("{} Schedule {} deleted", icons::success().green(), id);
 
     Ok(())
 }
 
-async fn pause_schedule(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
+async fn pause_schedule(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    id: &str,
+) -> CliResult<()> {
     let spinner = create_spinner("Pausing schedule...");
-    let schedule = client.pause_schedule(id).await.map_err(CliError::Sdk)?;
+    let schedule = retry_async(
+        || async { client.pause_schedule(id).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
     println!(
         "{} Schedule {} paused",
-        "⏸".yellow(),
+        icons::paused().yellow(),
         schedule.name.bold()
     );
 
     Ok(())
 }
 
-async fn resume_schedule(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
+async fn resume_schedule(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    id: &str,
+) -> CliResult<()> {
     let spinner = create_spinner("Resuming schedule...");
-    let schedule = client.resume_schedule(id).await.map_err(CliError::Sdk)?;
+    let schedule = retry_async(
+        || async { client.resume_schedule(id).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
     println!(
         "{} Schedule {} resumed",
-        "▶".green(),
+        icons::resumed().green(),
         schedule.name.bold()
     );
     if let Some(ref next) = schedule.next_execution_at {
@@ -337,32 +466,96 @@ async fn resume_schedule(client: &AllscreenshotsClient, id: &str) -> CliResult<(
     Ok(())
 }
 
-async fn trigger_schedule(client: &AllscreenshotsClient, id: &str) -> CliResult<()> {
+async fn trigger_schedule(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    id: &str,
+) -> CliResult<()> {
+    // Each trigger runs the schedule again, so only retry if it never reached the API
+    let policy = RetryPolicy {
+        retry_if: is_connect_failure,
+        ..policy.clone()
+    };
     let spinner = create_spinner("Triggering schedule...");
-    let schedule = client.trigger_schedule(id).await.map_err(CliError::Sdk)?;
+    let schedule = retry_async(
+        || async { client.trigger_schedule(id).await.map_err(CliError::Sdk) },
+        &policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
     println!(
         "{} Schedule {} triggered",
-        "⚡".cyan(),
+        icons::triggered().cyan(),
         schedule.name.bold()
     );
 
     Ok(())
 }
 
+/// Client-side filters for `schedule history`
+struct HistoryFilter {
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    status: Option<&'static str>,
+}
+
+impl HistoryFilter {
+    fn parse(since: Option<&str>, status: Option<&str>) -> CliResult<Self> {
+        let status = match status.map(str::to_lowercase).as_deref() {
+            None => None,
+            Some("completed") => Some("COMPLETED"),
+            Some("failed") => Some("FAILED"),
+            Some(other) => {
+                return Err(CliError::Other(format!(
+                    "Invalid status '{}'. Use: completed or failed",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            since: since.map(parse_since).transpose()?,
+            status,
+        })
+    }
+
+    fn matches(&self, exec: &ScheduleExecutionResponse) -> bool {
+        let status_ok = self.status.map_or(true, |s| exec.status.eq_ignore_ascii_case(s));
+        // Keep entries whose timestamp can't be read rather than hiding them
+        let since_ok = self.since.map_or(true, |since| {
+            chrono::DateTime::parse_from_rfc3339(&exec.executed_at)
+                .map_or(true, |at| at >= since)
+        });
+        status_ok && since_ok
+    }
+}
+
 async fn get_history(
     client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
     id: &str,
     limit: Option<i32>,
+    filter: &HistoryFilter,
+    json: bool,
 ) -> CliResult<()> {
     let spinner = create_spinner("Fetching history...");
-    let history = client
-        .get_schedule_history(id, limit)
-        .await
-        .map_err(CliError::Sdk)?;
+    let mut history = retry_async(
+        || async { client.get_schedule_history(id, limit).await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await?;
     spinner.finish_and_clear();
 
+    let fetched = history.executions.len();
+    history.executions.retain(|exec| filter.matches(exec));
+
+    if json {
+        let json = serde_json::to_string_pretty(&history)
+            .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
     println!(
         "{} ({})",
         "Execution History".bold().underline(),
@@ -370,6 +563,11 @@ async fn get_history(
     );
     println!();
 
+    if history.executions.is_empty() && fetched > 0 {
+        println!("{}", format!("None of the last {} executions match.", fetched).dimmed());
+        return Ok(());
+    }
+
     if history.executions.is_empty() {
         println!("{}", "No executions yet.".dimmed());
         return Ok(());
@@ -377,9 +575,9 @@ async fn get_history(
 
     for exec in history.executions {
         let status_icon = match exec.status.as_str() {
-            "COMPLETED" => "✓".green(),
-            "FAILED" => "✗".red(),
-            _ => "•".dimmed(),
+            "COMPLETED" => icons::success().green(),
+            "FAILED" => icons::failure().red(),
+            _ => icons::bullet().dimmed(),
         };
 
         println!(
@@ -0,0 +1,98 @@
+use crate::display::create_spinner;
+use crate::error::{CliError, CliResult};
+use clap::Args;
+use colored::Colorize;
+use self_update::backends::github;
+use self_update::update::ReleaseUpdate;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Args, Debug)]
+pub struct UpdateArgs {
+    /// Only report whether a newer release is available
+    #[arg(long)]
+    pub check: bool,
+
+    /// Don't ask for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// Replace this binary with the latest GitHub release
+pub async fn execute(args: UpdateArgs) -> CliResult<()> {
+    let spinner = create_spinner("Checking for updates...");
+    let latest = tokio::task::spawn_blocking(|| updater()?.get_latest_release())
+        .await
+        .map_err(|e| CliError::Other(format!("Update check failed: {}", e)))?
+        .map_err(update_error);
+    spinner.finish_and_clear();
+    let latest = latest?;
+
+    let newer = self_update::version::bump_is_greater(VERSION, &latest.version)
+        .map_err(update_error)?;
+    if !newer {
+        println!("{} allscreenshots {} is the latest version", "Up to date:".green(), VERSION);
+        return Ok(());
+    }
+
+    println!(
+        "{} {} -> {}",
+        "Update available:".yellow().bold(),
+        VERSION,
+        latest.version.green()
+    );
+    if args.check {
+        println!("{}", "Run `allscreenshots self-update` to install it".dimmed());
+        return Ok(());
+    }
+
+    if !args.yes {
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!("Replace this binary with {}?", latest.version))
+            .default(false)
+            .interact()
+            .map_err(|e| {
+                CliError::Other(format!("Couldn't ask for confirmation ({}). Pass --yes to skip it", e))
+            })?;
+        if !confirmed {
+            println!("{}", "Aborted, nothing was changed.".dimmed());
+            return Ok(());
+        }
+    }
+
+    let status = tokio::task::spawn_blocking(|| updater()?.update())
+        .await
+        .map_err(|e| CliError::Other(format!("Update failed: {}", e)))?
+        .map_err(update_error)?;
+
+    println!("{} Updated to {}", "Done:".green().bold(), status.version());
+    Ok(())
+}
+
+fn updater() -> self_update::errors::Result<Box<dyn ReleaseUpdate>> {
+    github::Update::configure()
+        .repo_owner("allscreenshots")
+        .repo_name("allscreenshots-cli")
+        .bin_name("allscreenshots")
+        .current_version(VERSION)
+        .show_download_progress(true)
+        // Confirmation already happened above
+        .no_confirm(true)
+        .build()
+}
+
+fn update_error(e: self_update::errors::Error) -> CliError {
+    match e {
+        self_update::errors::Error::Io(ref io) if io.kind() == std::io::ErrorKind::PermissionDenied => {
+            CliError::Other(format!(
+                "Can't replace the binary: {}. Re-run with permission to write to {}, \
+                 or update through the package manager that installed it",
+                io,
+                std::env::current_exe()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|_| "its directory".to_string())
+            ))
+        }
+        other => CliError::Other(format!("Update failed: {}", other)),
+    }
+}
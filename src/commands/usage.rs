@@ -1,121 +1,296 @@
-use crate::display::{create_spinner, UsageGraph};
+use crate::display::{create_spinner, icons, Table, UsageGraph};
+use crate::context::Context;
 use crate::error::{CliError, CliResult};
+use crate::retry::{retry_async, RetryPolicy};
+use crate::utils::save_to_file;
+use allscreenshots_sdk::models::{PeriodUsageResponse, QuotaStatusResponse, UsageResponse};
 use allscreenshots_sdk::AllscreenshotsClient;
 use clap::Args;
 use colored::Colorize;
+use std::fmt::Write;
+use std::path::PathBuf;
 
 #[derive(Args, Debug)]
 pub struct UsageArgs {
-    /// Output format: table, graph, json
+    /// Output format: table, graph, json, csv
     #[arg(long, default_value = "graph")]
     pub format: String,
 
     /// Show quota status only (simpler view)
     #[arg(long)]
     pub quota_only: bool,
+
+    /// Compare the current period with the previous one
+    #[arg(long)]
+    pub compare: bool,
+
+    /// Write the report to a file instead of stdout (colors are stripped)
+    #[arg(short, long, id = "output", value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct QuotaArgs {
+    /// Exit non-zero if fewer than N screenshots remain; prints nothing unless --verbose
+    #[arg(long, value_name = "N")]
+    pub exit_if_below: Option<u32>,
 }
 
-pub async fn execute(args: UsageArgs, api_key: Option<String>) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
+pub async fn execute(args: UsageArgs, ctx: &Context) -> CliResult<()> {
+    let client = ctx.client()?;
+    let policy = ctx.retry_policy();
 
-    if args.quota_only {
-        return show_quota(&client).await;
+    let report = if args.quota_only {
+        show_quota(&client, &policy).await?
+    } else {
+        match args.format.as_str() {
+            "json" => show_usage_json(&client, &policy).await?,
+            "csv" => show_usage_csv(&client, &policy).await?,
+            "table" => show_usage_table(&client, &policy, args.compare).await?,
+            _ => show_usage_graph(&client, &policy, args.compare).await?,
+        }
+    };
+
+    match args.output {
+        Some(ref path) => {
+            let plain = console::strip_ansi_codes(&report);
+            save_to_file(path, plain.trim_start_matches('\n').as_bytes())?;
+            println!("{} Saved: {}", icons::success().green(), path.display());
+        }
+        None => print!("{}", report),
     }
 
-    match args.format.as_str() {
-        "json" => show_usage_json(&client).await,
-        "table" => show_usage_table(&client).await,
-        _ => show_usage_graph(&client).await,
+    Ok(())
+}
+
+/// Show the quota, or check remaining screenshots against --exit-if-below
+pub async fn quota(args: QuotaArgs, ctx: &Context) -> CliResult<()> {
+    let client = ctx.client()?;
+    let policy = ctx.retry_policy();
+
+    let Some(minimum) = args.exit_if_below else {
+        print!("{}", show_quota(&client, &policy).await?);
+        return Ok(());
+    };
+
+    let quota = fetch_quota(&client, &policy).await?;
+    check_remaining(quota.screenshots.remaining, minimum)?;
+
+    if ctx.verbose {
+        println!(
+            "{} {} screenshots remaining (at least {} required)",
+            icons::success().green(),
+            quota.screenshots.remaining,
+            minimum
+        );
     }
+
+    Ok(())
+}
+
+async fn fetch_usage(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+) -> CliResult<UsageResponse> {
+    retry_async(
+        || async { client.get_usage().await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await
+}
+
+async fn fetch_quota(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+) -> CliResult<QuotaStatusResponse> {
+    retry_async(
+        || async { client.get_quota().await.map_err(CliError::Sdk) },
+        policy,
+    )
+    .await
 }
 
-async fn show_usage_graph(client: &AllscreenshotsClient) -> CliResult<()> {
+fn check_remaining(remaining: i32, minimum: u32) -> CliResult<()> {
+    if (remaining as i64) < minimum as i64 {
+        return Err(CliError::QuotaBelow { remaining, minimum });
+    }
+    Ok(())
+}
+
+async fn show_usage_graph(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    compare: bool,
+) -> CliResult<String> {
     let spinner = create_spinner("Fetching usage data...");
-    let usage = client.get_usage().await.map_err(CliError::Sdk)?;
+    let usage = fetch_usage(client, policy).await?;
     spinner.finish_and_clear();
 
-    UsageGraph::render_usage_summary(&usage);
+    let mut out = String::new();
+    UsageGraph::render_usage_summary(&mut out, &usage);
 
-    Ok(())
+    if compare {
+        UsageGraph::render_period_comparison(&mut out, &usage);
+    }
+
+    Ok(out)
 }
 
-async fn show_quota(client: &AllscreenshotsClient) -> CliResult<()> {
+async fn show_quota(client: &AllscreenshotsClient, policy: &RetryPolicy) -> CliResult<String> {
     let spinner = create_spinner("Fetching quota...");
-    let quota = client.get_quota().await.map_err(CliError::Sdk)?;
+    let quota = fetch_quota(client, policy).await?;
     spinner.finish_and_clear();
 
-    UsageGraph::render_quota_status(&quota);
+    let mut out = String::new();
+    UsageGraph::render_quota_status(&mut out, &quota);
 
-    Ok(())
+    Ok(out)
 }
 
-async fn show_usage_table(client: &AllscreenshotsClient) -> CliResult<()> {
+async fn show_usage_table(
+    client: &AllscreenshotsClient,
+    policy: &RetryPolicy,
+    compare: bool,
+) -> CliResult<String> {
     let spinner = create_spinner("Fetching usage data...");
-    let usage = client.get_usage().await.map_err(CliError::Sdk)?;
+    let usage = fetch_usage(client, policy).await?;
     spinner.finish_and_clear();
 
-    println!("\n{}", "API Usage".bold().underline());
-    println!();
-
-    // Tier info
-    println!("{:<20} {}", "Tier:", usage.tier.cyan());
+    let mut out = String::new();
+    let _ = writeln!(out, "\n{}", "API Usage".bold().underline());
+    let _ = writeln!(out);
+    let mut table = Table::new(&["Metric", "Value"]);
+    table.add_row(vec!["Tier".to_string(), usage.tier.cyan().to_string()]);
 
     // Current period (not Option)
     let period = &usage.current_period;
-    println!();
-    println!("{}", "Current Period".bold());
-    println!("{:<20} {}", "  Start:", period.period_start);
-    println!("{:<20} {}", "  End:", period.period_end);
-    println!("{:<20} {}", "  Screenshots:", period.screenshots_count);
-    println!("{:<20} {}", "  Bandwidth:", period.bandwidth_formatted);
+    table.add_row(vec!["Period start".to_string(), period.period_start.clone()]);
+    table.add_row(vec!["Period end".to_string(), period.period_end.clone()]);
+    table.add_row(vec!["Screenshots".to_string(), period.screenshots_count.to_string()]);
+    table.add_row(vec!["Bandwidth".to_string(), period.bandwidth_formatted.clone()]);
 
     // Quota
     if let Some(ref quota) = usage.quota {
-        println!();
-        println!("{}", "Quota".bold());
-        println!(
-            "{:<20} {} / {} ({}% used)",
-            "  Screenshots:",
-            quota.screenshots.used,
-            quota.screenshots.limit,
-            quota.screenshots.percent_used
-        );
-        println!(
-            "{:<20} {}",
-            "  Remaining:",
-            quota.screenshots.remaining.to_string().green()
-        );
-
-        println!(
-            "{:<20} {} / {} ({}% used)",
-            "  Bandwidth:",
-            quota.bandwidth.used_formatted,
-            quota.bandwidth.limit_formatted,
-            quota.bandwidth.percent_used
-        );
+        table.add_row(vec![
+            "Screenshot quota".to_string(),
+            format!(
+                "{} / {} ({}% used)",
+                quota.screenshots.used, quota.screenshots.limit, quota.screenshots.percent_used
+            ),
+        ]);
+        table.add_row(vec![
+            "Remaining".to_string(),
+            quota.screenshots.remaining.to_string().green().to_string(),
+        ]);
+        table.add_row(vec![
+            "Bandwidth quota".to_string(),
+            format!(
+                "{} / {} ({}% used)",
+                quota.bandwidth.used_formatted,
+                quota.bandwidth.limit_formatted,
+                quota.bandwidth.percent_used
+            ),
+        ]);
     }
 
     // Totals
     if let Some(ref totals) = usage.totals {
-        println!();
-        println!("{}", "All-Time Totals".bold());
-        println!("{:<20} {}", "  Screenshots:", totals.screenshots_count);
-        println!("{:<20} {}", "  Bandwidth:", totals.bandwidth_formatted);
+        table.add_row(vec![
+            "All-time screenshots".to_string(),
+            totals.screenshots_count.to_string(),
+        ]);
+        table.add_row(vec![
+            "All-time bandwidth".to_string(),
+            totals.bandwidth_formatted.clone(),
+        ]);
     }
 
-    println!();
+    for line in table.render() {
+        let _ = writeln!(out, "{}", line);
+    }
 
-    Ok(())
+    if compare {
+        UsageGraph::render_period_comparison(&mut out, &usage);
+    }
+
+    let _ = writeln!(out);
+
+    Ok(out)
 }
 
-async fn show_usage_json(client: &AllscreenshotsClient) -> CliResult<()> {
-    let usage = client.get_usage().await.map_err(CliError::Sdk)?;
+async fn show_usage_json(client: &AllscreenshotsClient, policy: &RetryPolicy) -> CliResult<String> {
+    let usage = fetch_usage(client, policy).await?;
 
     let json = serde_json::to_string_pretty(&usage)
         .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
 
-    println!("{}", json);
+    Ok(format!("{}\n", json))
+}
 
-    Ok(())
+async fn show_usage_csv(client: &AllscreenshotsClient, policy: &RetryPolicy) -> CliResult<String> {
+    let usage = fetch_usage(client, policy).await?;
+    Ok(usage_csv(&usage))
+}
+
+/// One row per billing period, oldest first, including the current one
+fn usage_csv(usage: &UsageResponse) -> String {
+    let mut periods: Vec<&PeriodUsageResponse> = usage.history.iter().flatten().collect();
+    if !periods.iter().any(|p| p.period_start == usage.current_period.period_start) {
+        periods.push(&usage.current_period);
+    }
+    periods.sort_by(|a, b| a.period_start.cmp(&b.period_start));
+
+    let mut out = String::from("period_start,period_end,screenshots,bandwidth_bytes\n");
+    for period in periods {
+        let _ = writeln!(
+            out,
+            "{},{},{},{}",
+            period.period_start, period.period_end, period.screenshots_count, period.bandwidth_bytes
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(start: &str, screenshots: i32) -> PeriodUsageResponse {
+        PeriodUsageResponse {
+            period_start: start.to_string(),
+            period_end: String::new(),
+            screenshots_count: screenshots,
+            bandwidth_bytes: 1024,
+            bandwidth_formatted: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_usage_csv() {
+        let usage = UsageResponse {
+            tier: "free".to_string(),
+            current_period: period("2024-03-01", 7),
+            quota: None,
+            history: Some(vec![period("2024-03-01", 7), period("2024-01-01", 3)]),
+            totals: None,
+        };
+
+        assert_eq!(
+            usage_csv(&usage),
+            "period_start,period_end,screenshots,bandwidth_bytes\n\
+             2024-01-01,,3,1024\n\
+             2024-03-01,,7,1024\n"
+        );
+    }
+
+    #[test]
+    fn test_check_remaining() {
+        assert!(check_remaining(500, 500).is_ok());
+        assert!(check_remaining(0, 0).is_ok());
+
+        let err = check_remaining(499, 500).unwrap_err();
+        assert!(err.is_quiet());
+        assert_eq!(err.code(), "quota_below");
+        assert!(check_remaining(-3, 1).is_err());
+    }
 }
@@ -0,0 +1,59 @@
+use crate::context::Context;
+use crate::error::{CliError, CliResult};
+use colored::Colorize;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+const SDK_VERSION: &str = env!("ALLSCREENSHOTS_SDK_VERSION");
+const GIT_COMMIT: &str = env!("ALLSCREENSHOTS_GIT_COMMIT");
+const TARGET: &str = env!("ALLSCREENSHOTS_TARGET");
+
+/// Cargo features compiled into this binary
+fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "sixel") {
+        features.push("sixel");
+    }
+    if cfg!(feature = "self-update") {
+        features.push("self-update");
+    }
+    features
+}
+
+/// Print the CLI version; with --verbose, include build details for bug reports
+pub fn execute(ctx: &Context) -> CliResult<()> {
+    let features = enabled_features();
+
+    if ctx.json {
+        let info = serde_json::json!({
+            "version": VERSION,
+            "sdk_version": SDK_VERSION,
+            "git_commit": GIT_COMMIT,
+            "target": TARGET,
+            "features": features,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&info)
+                .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?
+        );
+        return Ok(());
+    }
+
+    println!("allscreenshots {}", VERSION);
+
+    if ctx.verbose {
+        println!("  SDK: {}", SDK_VERSION);
+        println!("  Commit: {}", GIT_COMMIT);
+        println!("  Target: {}", TARGET);
+        println!(
+            "  Features: {}",
+            if features.is_empty() {
+                "none".dimmed().to_string()
+            } else {
+                features.join(", ")
+            }
+        );
+    }
+
+    Ok(())
+}
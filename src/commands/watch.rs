@@ -1,11 +1,19 @@
-use crate::display::{create_spinner, TerminalImage};
+use crate::context::Context;
+use crate::display::{create_spinner, icons, TerminalImage};
 use crate::error::{CliError, CliResult};
-use crate::utils::{auto_filename, normalize_url, save_to_file};
-use allscreenshots_sdk::{AllscreenshotsClient, ImageFormat, ScreenshotRequest};
+use crate::rate_limit::RateLimiter;
+use crate::utils::{
+    auto_filename, expand_device_alias, normalize_url, parse_duration, render_output_template, save_to_file,
+    update_latest, TemplateVars,
+};
+use allscreenshots_sdk::{ImageFormat, ScreenshotRequest};
 use clap::Args;
 use colored::Colorize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, ChildStdin, Command};
 use tokio::time::sleep;
 
 #[derive(Args, Debug)]
@@ -19,9 +27,13 @@ pub struct WatchArgs {
     pub interval: String,
 
     /// Output directory for saved screenshots
-    #[arg(short, long)]
+    #[arg(short, long, id = "output", value_name = "DIR")]
     pub output_dir: Option<PathBuf>,
 
+    /// Keep latest.<format> in the output directory pointing at the newest capture
+    #[arg(long, requires = "output")]
+    pub latest: bool,
+
     /// Device preset
     #[arg(short, long)]
     pub device: Option<String>,
@@ -38,17 +50,63 @@ pub struct WatchArgs {
     #[arg(long, default_value = "0")]
     pub max_captures: u32,
 
+    /// Allow an --interval below 1s (every capture uses quota)
+    #[arg(long)]
+    pub force: bool,
+
+    /// Start at most N captures per second, however short --interval is
+    #[arg(long, value_name = "N", value_parser = crate::rate_limit::parse_rate)]
+    pub rate: Option<f64>,
+
     /// Don't display in terminal
     #[arg(long)]
     pub no_display: bool,
+
+    /// Build an MP4 timelapse of the captures (requires ffmpeg)
+    #[arg(long, value_name = "PATH")]
+    pub video: Option<PathBuf>,
+
+    /// Frames per second for --video
+    #[arg(long, default_value = "2", requires = "video")]
+    pub video_fps: u32,
 }
 
-pub async fn execute(args: WatchArgs, api_key: Option<String>) -> CliResult<()> {
-    let api_key = api_key.ok_or(CliError::NoApiKey)?;
+/// Shortest --interval accepted without --force
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+pub async fn execute(args: WatchArgs, ctx: &Context) -> CliResult<()> {
+    let client = ctx.client()?;
     let url = normalize_url(&args.url)?;
 
     // Parse interval
     let interval = parse_duration(&args.interval)?;
+    check_interval(interval, args.force)?;
+    if interval < MIN_INTERVAL {
+        eprintln!(
+            "{} Capturing every {} uses up to {} screenshots of quota per minute",
+            "Warning:".yellow(),
+            humantime::format_duration(interval),
+            captures_per_minute(interval)
+        );
+    }
+
+    // Never plan more captures than the quota has left; skip the check if it's slow
+    let mut max_captures = args.max_captures;
+    let quota = tokio::time::timeout(Duration::from_secs(5), client.get_quota()).await;
+    if let Ok(Ok(quota)) = quota {
+        let remaining = quota.screenshots.remaining;
+        if remaining <= 0 {
+            return Err(CliError::Other("No screenshots left in your quota".to_string()));
+        }
+        if let Some(cap) = quota_cap(max_captures, remaining) {
+            eprintln!(
+                "{} Stopping after {} captures, the screenshots left in your quota",
+                "Note:".yellow(),
+                cap
+            );
+            max_captures = cap;
+        }
+    }
 
     // Parse format
     let format = match args.format.to_lowercase().as_str() {
@@ -62,34 +120,45 @@ pub async fn execute(args: WatchArgs, api_key: Option<String>) -> CliResult<()>
     println!("  URL: {}", url);
     println!(
         "  Interval: {}",
-        humantime::format_duration(interval).to_string()
+        humantime::format_duration(interval)
     );
     if let Some(ref dir) = args.output_dir {
         println!("  Output: {}", dir.display());
     }
-    if args.max_captures > 0 {
-        println!("  Max captures: {}", args.max_captures);
+    if max_captures > 0 {
+        println!("  Max captures: {}", max_captures);
     }
+
+    let mut video = match args.video {
+        Some(ref path) => {
+            let writer = VideoWriter::spawn(path, args.video_fps)?;
+            println!("  Video: {} ({} fps)", path.display(), args.video_fps);
+            Some(writer)
+        }
+        None => None,
+    };
+
     println!();
     println!("{}", "Press Ctrl+C to stop".dimmed());
     println!();
 
-    // Create client
-    let client = AllscreenshotsClient::new(&api_key).map_err(CliError::Sdk)?;
-
     // Build request template
     let mut builder = ScreenshotRequest::builder()
         .url(&url)
         .format(format);
 
     if let Some(ref device) = args.device {
-        builder = builder.device(device);
+        builder = builder.device(expand_device_alias(device));
     }
 
     if args.full_page {
         builder = builder.full_page(true);
     }
 
+    if let Some(css) = ctx.block_css() {
+        builder = builder.custom_css(css);
+    }
+
     let request = builder.build().map_err(|e| CliError::Other(e.to_string()))?;
 
     // Ensure output directory exists
@@ -97,14 +166,55 @@ pub async fn execute(args: WatchArgs, api_key: Option<String>) -> CliResult<()>
         crate::utils::ensure_dir(dir)?;
     }
 
+    // File names from --output-template, checked before the first capture
+    let file_name = |index: u32| -> CliResult<String> {
+        match ctx.output_template {
+            Some(ref template) => {
+                let vars = TemplateVars {
+                    url: &url,
+                    device: args.device.as_deref(),
+                    format: &args.format,
+                    index: index as usize,
+                };
+                render_output_template(template, &vars)
+            }
+            None => Ok(auto_filename(&url, &args.format)),
+        }
+    };
+    file_name(1)?;
+
     let mut capture_count = 0u32;
+    let mut ctrl_c = std::pin::pin!(tokio::signal::ctrl_c());
+    let limiter = args.rate.map(RateLimiter::per_second);
 
     loop {
         capture_count += 1;
 
+        if let Some(ref limiter) = limiter {
+            let waited = tokio::select! {
+                waited = limiter.acquire() => waited,
+                _ = &mut ctrl_c => {
+                    println!("\n{}", "Stopped".dimmed());
+                    break;
+                }
+            };
+            if !waited.is_zero() && ctx.verbose {
+                eprintln!("  {}", format!("Throttled for {:.1}s (--rate)", waited.as_secs_f64()).dimmed());
+            }
+        }
+
         let spinner = create_spinner(&format!("Capture #{}: {}...", capture_count, url));
 
-        match client.screenshot(&request).await {
+        let result = tokio::select! {
+            result = client.screenshot(&request) => result,
+            _ = &mut ctrl_c => {
+                spinner.finish_and_clear();
+                println!("\n{}", "Stopped".dimmed());
+                break;
+            }
+        };
+
+        match result {
             Ok(image_bytes) => {
                 spinner.finish_and_clear();
 
@@ -113,12 +223,18 @@ pub async fn execute(args: WatchArgs, api_key: Option<String>) -> CliResult<()>
 
                 // Save to file if output directory specified
                 if let Some(ref dir) = args.output_dir {
-                    let filename = auto_filename(&url, &args.format);
+                    let filename = file_name(capture_count)?;
                     let path = dir.join(&filename);
                     save_to_file(&path, &image_bytes)?;
-                    print!("  {} Saved: {} ", "✓".green(), filename);
+                    if args.latest {
+                        // A stale link shouldn't stop the watch
+                        if let Err(e) = update_latest(dir, Path::new(&filename), &args.format) {
+                            eprintln!("{} {}", "Warning:".yellow(), e);
+                        }
+                    }
+                    print!("  {} Saved: {} ", icons::success().green(), filename);
                 } else {
-                    print!("  {} Captured ", "✓".green());
+                    print!("  {} Captured ", icons::success().green());
                 }
 
                 // Print size info
@@ -129,6 +245,14 @@ pub async fn execute(args: WatchArgs, api_key: Option<String>) -> CliResult<()>
                 }
                 println!();
 
+                // Append frame to the video
+                if let Some(ref mut writer) = video {
+                    if let Err(e) = writer.write_frame(&image_bytes).await {
+                        eprintln!("  {} {}", icons::failure().red(), e);
+                        video = None;
+                    }
+                }
+
                 // Display in terminal
                 if !args.no_display {
                     let display = TerminalImage::with_size(60, 20);
@@ -138,13 +262,13 @@ pub async fn execute(args: WatchArgs, api_key: Option<String>) -> CliResult<()>
             }
             Err(e) => {
                 spinner.finish_and_clear();
-                eprintln!("  {} Capture failed: {}", "✗".red(), e);
+                eprintln!("  {} Capture failed: {}", icons::failure().red(), e);
             }
         }
 
         // Check max captures
-        if args.max_captures > 0 && capture_count >= args.max_captures {
-            println!("\n{} Maximum captures ({}) reached", "✓".green(), args.max_captures);
+        if max_captures > 0 && capture_count >= max_captures {
+            println!("\n{} Maximum captures ({}) reached", icons::success().green(), max_captures);
             break;
         }
 
@@ -153,19 +277,150 @@ pub async fn execute(args: WatchArgs, api_key: Option<String>) -> CliResult<()>
             "Waiting {}...",
             humantime::format_duration(interval)
         ));
-        sleep(interval).await;
-        wait_spinner.finish_and_clear();
+        tokio::select! {
+            _ = sleep(interval) => wait_spinner.finish_and_clear(),
+            _ = &mut ctrl_c => {
+                wait_spinner.finish_and_clear();
+                println!("\n{}", "Stopped".dimmed());
+                break;
+            }
+        }
+    }
+
+    if let Some(report) = limiter.as_ref().and_then(RateLimiter::report) {
+        println!("{}", format!("{} to stay under --rate", report).dimmed());
+    }
+
+    if let Some(writer) = video {
+        let spinner = create_spinner("Finalizing video...");
+        let result = writer.finish().await;
+        spinner.finish_and_clear();
+        let path = result?;
+        println!("{} Video saved: {}", icons::success().green(), path.display().to_string().cyan());
     }
 
     Ok(())
 }
 
-fn parse_duration(s: &str) -> CliResult<Duration> {
-    // Try to parse as humantime format (e.g., "5s", "1m", "30s")
-    humantime::parse_duration(s).map_err(|_| {
-        CliError::Other(format!(
-            "Invalid duration '{}'. Examples: 5s, 30s, 1m, 5m",
-            s
-        ))
-    })
+/// Streams captured frames into an ffmpeg process that encodes an MP4
+struct VideoWriter {
+    child: Child,
+    stdin: ChildStdin,
+    path: PathBuf,
+}
+
+impl VideoWriter {
+    fn spawn(path: &Path, fps: u32) -> CliResult<Self> {
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-hide_banner", "-loglevel", "error", "-y"])
+            .args(["-f", "image2pipe", "-framerate", &fps.max(1).to_string(), "-i", "-"])
+            // H.264 needs even dimensions
+            .args(["-vf", "scale=trunc(iw/2)*2:trunc(ih/2)*2"])
+            .args(["-c:v", "libx264", "-pix_fmt", "yuv420p"])
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null());
+
+        // Keep ffmpeg out of our process group so Ctrl+C lets us finalize the file
+        #[cfg(unix)]
+        command.process_group(0);
+
+        let mut child = command.spawn().map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                CliError::Other(
+                    "ffmpeg not found. Install ffmpeg to use --video, or use --output-dir to keep individual frames"
+                        .to_string(),
+                )
+            } else {
+                CliError::Other(format!("Failed to start ffmpeg: {}", e))
+            }
+        })?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| CliError::Other("Failed to open ffmpeg input".to_string()))?;
+
+        Ok(Self {
+            child,
+            stdin,
+            path: path.to_path_buf(),
+        })
+    }
+
+    async fn write_frame(&mut self, image_bytes: &[u8]) -> CliResult<()> {
+        self.stdin
+            .write_all(image_bytes)
+            .await
+            .map_err(|e| CliError::Other(format!("Failed to write video frame: {}", e)))
+    }
+
+    async fn finish(self) -> CliResult<PathBuf> {
+        let Self { mut child, stdin, path } = self;
+
+        // Closing stdin tells ffmpeg the stream has ended
+        drop(stdin);
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|e| CliError::Other(format!("Failed to wait for ffmpeg: {}", e)))?;
+
+        if !status.success() {
+            return Err(CliError::Other(format!("ffmpeg exited with {}", status)));
+        }
+
+        Ok(path)
+    }
+}
+
+/// Reject intervals that would hammer the API unless --force is given
+fn check_interval(interval: Duration, force: bool) -> CliResult<()> {
+    if interval >= MIN_INTERVAL || force {
+        return Ok(());
+    }
+    Err(CliError::Other(format!(
+        "Interval {} is below the {} minimum; that could use {} screenshots of quota per minute. \
+         Pass --force to use it anyway",
+        humantime::format_duration(interval),
+        humantime::format_duration(MIN_INTERVAL),
+        captures_per_minute(interval)
+    )))
+}
+
+/// Captures in a minute at this interval, ignoring how long each capture takes
+fn captures_per_minute(interval: Duration) -> String {
+    match 60_000u128.checked_div(interval.as_millis()) {
+        Some(count) => count.to_string(),
+        None => "unlimited".to_string(),
+    }
+}
+
+/// A lower capture limit when the remaining quota can't cover `max_captures` (0 = unlimited)
+fn quota_cap(max_captures: u32, remaining: i32) -> Option<u32> {
+    let remaining = u32::try_from(remaining).ok()?;
+    (max_captures == 0 || max_captures > remaining).then_some(remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_interval() {
+        assert!(check_interval(Duration::from_secs(1), false).is_ok());
+        assert!(check_interval(Duration::from_millis(500), false).is_err());
+        assert!(check_interval(Duration::ZERO, true).is_ok());
+        assert_eq!(captures_per_minute(Duration::from_millis(500)), "120");
+        assert_eq!(captures_per_minute(Duration::ZERO), "unlimited");
+    }
+
+    #[test]
+    fn test_quota_cap() {
+        assert_eq!(quota_cap(0, 40), Some(40));
+        assert_eq!(quota_cap(100, 40), Some(40));
+        assert_eq!(quota_cap(10, 40), None);
+        assert_eq!(quota_cap(0, -1), None);
+    }
 }
@@ -29,6 +29,9 @@ pub struct Config {
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct AuthConfig {
     pub api_key: Option<String>,
+    /// Custom API base URL (staging or self-hosted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_base: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,6 +40,21 @@ pub struct DefaultsConfig {
     pub format: Option<String>,
     pub output_dir: Option<String>,
     pub display: Option<bool>,
+    /// File name template, e.g. "{index}_{domain}.{format}"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_template: Option<String>,
+    /// Fixed interval for polling async jobs, e.g. "1s"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval: Option<String>,
+    /// Retries after a failed request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// Reject URLs without http:// or https:// instead of adding https://
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict_url: Option<bool>,
+    /// Selectors hidden on every capture
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_selectors: Vec<String>,
 }
 
 impl Default for DefaultsConfig {
@@ -46,6 +64,11 @@ impl Default for DefaultsConfig {
             format: Some("png".to_string()),
             output_dir: Some("./screenshots".to_string()),
             display: Some(true),
+            output_template: None,
+            poll_interval: None,
+            retries: None,
+            strict_url: None,
+            block_selectors: Vec::new(),
         }
     }
 }
@@ -55,6 +78,9 @@ pub struct DisplayConfig {
     pub protocol: Option<String>,
     pub width: Option<u32>,
     pub height: Option<u32>,
+    /// Cap on rendered image width in columns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<u32>,
 }
 
 impl Default for DisplayConfig {
@@ -63,6 +89,7 @@ impl Default for DisplayConfig {
             protocol: Some("auto".to_string()),
             width: Some(80),
             height: Some(24),
+            max_width: None,
         }
     }
 }
@@ -0,0 +1,117 @@
+use crate::error::{CliError, CliResult};
+use crate::retry::{PollSchedule, RetryPolicy};
+use crate::utils::hide_css;
+use allscreenshots_sdk::AllscreenshotsClient;
+
+/// Settings resolved from global flags, environment and config file
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    /// API key with priority: CLI > env > config
+    pub api_key: Option<String>,
+    /// Custom API endpoint (staging or self-hosted), already validated
+    pub api_base: Option<String>,
+    /// Print machine-readable JSON instead of human output
+    pub json: bool,
+    /// Print extra details such as remaining quota
+    pub verbose: bool,
+    /// File name template for saved screenshots: CLI > config
+    pub output_template: Option<String>,
+    /// Poll interval from the config, used when a command has no --poll-interval
+    pub poll_interval: Option<String>,
+    /// Retries after a failed request: CLI > config
+    pub retries: Option<u32>,
+    /// Selectors from the config hidden on every capture
+    pub block_selectors: Vec<String>,
+}
+
+impl Context {
+    /// Create an SDK client for the configured API key and endpoint.
+    ///
+    /// The SDK's own retries are off: `retry_policy` is the only retry layer, so
+    /// `--retries` is the real number of retries and job creation isn't repeated behind our back
+    pub fn client(&self) -> CliResult<AllscreenshotsClient> {
+        let api_key = self.api_key.as_deref().ok_or(CliError::NoApiKey)?;
+
+        let mut builder = AllscreenshotsClient::builder().api_key(api_key).max_retries(0);
+        if let Some(ref base) = self.api_base {
+            builder = builder.base_url(base);
+        }
+
+        builder.build().map_err(CliError::Sdk)
+    }
+
+    /// Poll schedule for async jobs; an explicit --poll-interval wins over the config
+    pub fn poll_schedule(&self, poll_interval: Option<&str>) -> CliResult<PollSchedule> {
+        PollSchedule::from_interval(poll_interval.or(self.poll_interval.as_deref()))
+    }
+
+    /// Retry policy with the configured number of retries
+    pub fn retry_policy(&self) -> RetryPolicy {
+        let mut policy = RetryPolicy::default();
+        if let Some(retries) = self.retries {
+            policy.max_attempts = retries.saturating_add(1);
+        }
+        policy
+    }
+
+    /// Config block selectors followed by a command's own --hide selectors, without repeats
+    pub fn hide_selectors(&self, extra: &[String]) -> Vec<String> {
+        let mut selectors: Vec<String> = Vec::new();
+        for selector in self.block_selectors.iter().chain(extra) {
+            if !selectors.contains(selector) {
+                selectors.push(selector.clone());
+            }
+        }
+        selectors
+    }
+
+    /// CSS hiding the config block selectors, for commands without --hide
+    pub fn block_css(&self) -> Option<String> {
+        hide_css(&self.block_selectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_poll_schedule_precedence() {
+        let ctx = Context {
+            poll_interval: Some("1s".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ctx.poll_schedule(None).unwrap(), PollSchedule::Fixed(Duration::from_secs(1)));
+        assert_eq!(
+            ctx.poll_schedule(Some("250ms")).unwrap(),
+            PollSchedule::Fixed(Duration::from_millis(250))
+        );
+        assert_eq!(Context::default().poll_schedule(None).unwrap(), PollSchedule::Adaptive);
+        assert!(ctx.poll_schedule(Some("soon")).is_err());
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        assert_eq!(Context::default().retry_policy().max_attempts, RetryPolicy::default().max_attempts);
+
+        let ctx = Context {
+            retries: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(ctx.retry_policy().max_attempts, 1);
+    }
+
+    #[test]
+    fn test_hide_selectors_merge() {
+        let ctx = Context {
+            block_selectors: vec!["#intercom".to_string(), ".promo".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            ctx.hide_selectors(&[".promo".to_string(), "#banner".to_string()]),
+            vec!["#intercom", ".promo", "#banner"]
+        );
+        assert!(Context::default().block_css().is_none());
+    }
+}
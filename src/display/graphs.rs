@@ -1,12 +1,14 @@
-use allscreenshots_sdk::models::{QuotaStatusResponse, UsageResponse};
+use allscreenshots_sdk::models::{PeriodUsageResponse, QuotaStatusResponse, UsageResponse};
+use super::icons;
 use colored::Colorize;
+use std::fmt::Write;
 
-/// ASCII graph rendering for usage statistics
+/// ASCII graph rendering for usage statistics, appended to a String so it can be printed or saved
 pub struct UsageGraph;
 
 impl UsageGraph {
     /// Render a horizontal bar for quota usage
-    pub fn render_quota_bar(used: i32, limit: i32, label: &str, bar_width: usize) {
+    pub fn render_quota_bar(out: &mut String, used: i32, limit: i32, label: &str, bar_width: usize) {
         let percent = if limit > 0 {
             (used as f32 / limit as f32 * 100.0) as i32
         } else {
@@ -29,11 +31,13 @@ impl UsageGraph {
             "green"
         };
 
-        let filled_bar = "█".repeat(filled);
-        let empty_bar = "░".repeat(empty);
+        let (filled_cell, empty_cell) = icons::bar_cells();
+        let filled_bar = filled_cell.repeat(filled);
+        let empty_bar = empty_cell.repeat(empty);
 
-        println!("\n{}", label.bold());
-        println!(
+        let _ = writeln!(out, "\n{}", label.bold());
+        let _ = writeln!(
+            out,
             "[{}{}] {}/{} ({}%)",
             filled_bar.color(bar_color),
             empty_bar.dimmed(),
@@ -45,6 +49,7 @@ impl UsageGraph {
 
     /// Render bandwidth usage bar
     pub fn render_bandwidth_bar(
+        out: &mut String,
         used_bytes: i64,
         limit_bytes: i64,
         used_formatted: &str,
@@ -72,11 +77,13 @@ impl UsageGraph {
             "green"
         };
 
-        let filled_bar = "█".repeat(filled);
-        let empty_bar = "░".repeat(empty);
+        let (filled_cell, empty_cell) = icons::bar_cells();
+        let filled_bar = filled_cell.repeat(filled);
+        let empty_bar = empty_cell.repeat(empty);
 
-        println!("\n{}", "Bandwidth".bold());
-        println!(
+        let _ = writeln!(out, "\n{}", "Bandwidth".bold());
+        let _ = writeln!(
+            out,
             "[{}{}] {} / {} ({}%)",
             filled_bar.color(bar_color),
             empty_bar.dimmed(),
@@ -87,12 +94,12 @@ impl UsageGraph {
     }
 
     /// Render a sparkline from historical data
-    pub fn render_sparkline(data: &[i32], label: &str) {
+    pub fn render_sparkline(out: &mut String, data: &[i32], label: &str) {
         if data.is_empty() {
             return;
         }
 
-        let chars = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let chars = icons::sparkline_levels();
         let max = *data.iter().max().unwrap_or(&1) as f32;
 
         let sparkline: String = data
@@ -107,22 +114,23 @@ impl UsageGraph {
             })
             .collect();
 
-        println!("\n{}", label.bold());
-        println!("{}", sparkline.cyan());
+        let _ = writeln!(out, "\n{}", label.bold());
+        let _ = writeln!(out, "{}", sparkline.cyan());
     }
 
     /// Render complete usage summary
-    pub fn render_usage_summary(usage: &UsageResponse) {
-        println!("\n{}", "═".repeat(50).dimmed());
-        println!("{}", "  API Usage Summary".bold().underline());
-        println!("{}", "═".repeat(50).dimmed());
+    pub fn render_usage_summary(out: &mut String, usage: &UsageResponse) {
+        let _ = writeln!(out, "\n{}", icons::rule(50).dimmed());
+        let _ = writeln!(out, "{}", "  API Usage Summary".bold().underline());
+        let _ = writeln!(out, "{}", icons::rule(50).dimmed());
 
         // Tier
-        println!("\n{}: {}", "Tier".bold(), usage.tier.cyan());
+        let _ = writeln!(out, "\n{}: {}", "Tier".bold(), usage.tier.cyan());
 
         // Current period (not optional)
         let period = &usage.current_period;
-        println!(
+        let _ = writeln!(
+            out,
             "\n{}: {} to {}",
             "Period".bold(),
             period.period_start.dimmed(),
@@ -130,27 +138,31 @@ impl UsageGraph {
         );
 
         // Show current period stats
-        println!("\n{}", "Current Period".bold());
-        println!(
+        let _ = writeln!(out, "\n{}", "Current Period".bold());
+        let _ = writeln!(
+            out,
             "  Screenshots: {}",
             Self::format_number_i32(period.screenshots_count).cyan()
         );
-        println!("  Bandwidth: {}", period.bandwidth_formatted.cyan());
+        let _ = writeln!(out, "  Bandwidth: {}", period.bandwidth_formatted.cyan());
 
         // Quota bars
         if let Some(ref quota) = usage.quota {
             Self::render_quota_bar(
+                out,
                 quota.screenshots.used,
                 quota.screenshots.limit,
                 "Screenshots",
                 40,
             );
-            println!(
+            let _ = writeln!(
+                out,
                 "  {} remaining",
                 quota.screenshots.remaining.to_string().green()
             );
 
             Self::render_bandwidth_bar(
+                out,
                 quota.bandwidth.used_bytes,
                 quota.bandwidth.limit_bytes,
                 &quota.bandwidth.used_formatted,
@@ -163,29 +175,31 @@ impl UsageGraph {
         if let Some(ref history) = usage.history {
             if !history.is_empty() {
                 let counts: Vec<i32> = history.iter().map(|h| h.screenshots_count).collect();
-                Self::render_sparkline(&counts, "Usage History (last periods)");
+                Self::render_sparkline(out, &counts, "Usage History (last periods)");
             }
         }
 
         // Totals
         if let Some(ref totals) = usage.totals {
-            println!("\n{}", "All-Time Totals".bold());
-            println!(
+            let _ = writeln!(out, "\n{}", "All-Time Totals".bold());
+            let _ = writeln!(
+                out,
                 "  Screenshots: {}",
                 Self::format_number_i64(totals.screenshots_count).cyan()
             );
-            println!("  Bandwidth: {}", totals.bandwidth_formatted.cyan());
+            let _ = writeln!(out, "  Bandwidth: {}", totals.bandwidth_formatted.cyan());
         }
 
-        println!("\n{}", "═".repeat(50).dimmed());
+        let _ = writeln!(out, "\n{}", icons::rule(50).dimmed());
     }
 
     /// Render quota status (simpler view)
-    pub fn render_quota_status(quota: &QuotaStatusResponse) {
-        println!("\n{}", "Quota Status".bold().underline());
-        println!("Tier: {}", quota.tier.cyan());
+    pub fn render_quota_status(out: &mut String, quota: &QuotaStatusResponse) {
+        let _ = writeln!(out, "\n{}", "Quota Status".bold().underline());
+        let _ = writeln!(out, "Tier: {}", quota.tier.cyan());
 
         Self::render_quota_bar(
+            out,
             quota.screenshots.used,
             quota.screenshots.limit,
             "Screenshots",
@@ -193,12 +207,14 @@ impl UsageGraph {
         );
 
         // Show remaining
-        println!(
+        let _ = writeln!(
+            out,
             "  {} remaining",
             quota.screenshots.remaining.to_string().green()
         );
 
         Self::render_bandwidth_bar(
+            out,
             quota.bandwidth.used_bytes,
             quota.bandwidth.limit_bytes,
             &quota.bandwidth.used_formatted,
@@ -207,7 +223,87 @@ impl UsageGraph {
         );
 
         if let Some(ref ends) = quota.period_ends {
-            println!("\nPeriod ends: {}", ends.dimmed());
+            let _ = writeln!(out, "\nPeriod ends: {}", ends.dimmed());
+        }
+    }
+
+    /// Render the change versus the previous period from usage history
+    pub fn render_period_comparison(out: &mut String, usage: &UsageResponse) {
+        let current = &usage.current_period;
+        let previous = usage
+            .history
+            .as_deref()
+            .and_then(|history| Self::previous_period(history, current));
+
+        let _ = writeln!(out, "\n{}", "Compared to Previous Period".bold());
+
+        let Some(previous) = previous else {
+            let _ = writeln!(out, "  {}", "Not enough history to compare yet".dimmed());
+            return;
+        };
+
+        let _ = writeln!(
+            out,
+            "  Previous: {} to {}",
+            previous.period_start.dimmed(),
+            previous.period_end.dimmed()
+        );
+
+        let screenshots = Self::describe_delta(
+            current.screenshots_count as i64,
+            previous.screenshots_count as i64,
+            Self::format_number_i64,
+        );
+        let _ = writeln!(
+            out,
+            "  Screenshots: {}",
+            Self::color_delta(&screenshots, current.screenshots_count as i64 - previous.screenshots_count as i64)
+        );
+
+        let bandwidth = Self::describe_delta(
+            current.bandwidth_bytes,
+            previous.bandwidth_bytes,
+            |n| crate::utils::format_file_size(n as u64),
+        );
+        let _ = writeln!(
+            out,
+            "  Bandwidth: {}",
+            Self::color_delta(&bandwidth, current.bandwidth_bytes - previous.bandwidth_bytes)
+        );
+    }
+
+    /// Find the most recent period in history that started before the current one
+    fn previous_period<'a>(
+        history: &'a [PeriodUsageResponse],
+        current: &PeriodUsageResponse,
+    ) -> Option<&'a PeriodUsageResponse> {
+        history
+            .iter()
+            .filter(|p| p.period_start < current.period_start)
+            .max_by(|a, b| a.period_start.cmp(&b.period_start))
+    }
+
+    /// Describe a change, e.g. "+1,204 vs last period (+18%)"
+    fn describe_delta(current: i64, previous: i64, format: impl Fn(i64) -> String) -> String {
+        let delta = current - previous;
+        let sign = if delta < 0 { "-" } else { "+" };
+        let mut text = format!("{}{} vs last period", sign, format(delta.abs()));
+
+        if previous > 0 {
+            let percent = delta as f64 / previous as f64 * 100.0;
+            text.push_str(&format!(" ({}{:.0}%)", sign, percent.abs()));
+        }
+
+        text
+    }
+
+    fn color_delta(text: &str, delta: i64) -> colored::ColoredString {
+        if delta > 0 {
+            text.green()
+        } else if delta < 0 {
+            text.red()
+        } else {
+            text.dimmed()
         }
     }
 
@@ -248,6 +344,32 @@ impl UsageGraph {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_describe_delta() {
+        let fmt = UsageGraph::format_number_i64;
+        assert_eq!(UsageGraph::describe_delta(7_892, 6_688, fmt), "+1,204 vs last period (+18%)");
+        assert_eq!(UsageGraph::describe_delta(50, 100, fmt), "-50 vs last period (-50%)");
+        assert_eq!(UsageGraph::describe_delta(10, 10, fmt), "+0 vs last period (+0%)");
+        assert_eq!(UsageGraph::describe_delta(10, 0, fmt), "+10 vs last period");
+    }
+
+    #[test]
+    fn test_previous_period() {
+        let period = |start: &str| PeriodUsageResponse {
+            period_start: start.to_string(),
+            period_end: String::new(),
+            screenshots_count: 0,
+            bandwidth_bytes: 0,
+            bandwidth_formatted: String::new(),
+        };
+        let current = period("2024-03-01");
+        let history = vec![period("2024-01-01"), period("2024-03-01"), period("2024-02-01")];
+
+        let previous = UsageGraph::previous_period(&history, &current).unwrap();
+        assert_eq!(previous.period_start, "2024-02-01");
+        assert!(UsageGraph::previous_period(&history[1..2], &current).is_none());
+    }
+
     #[test]
     fn test_format_number() {
         assert_eq!(UsageGraph::format_number_i32(1000), "1,000");
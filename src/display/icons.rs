@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Switch every icon and bar to ASCII (see `--plain`)
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+/// Whether ASCII-only output is enabled
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// Check a locale value like "en_US.UTF-8"; unset locales count as UTF-8
+fn is_utf8_locale(locale: Option<&str>) -> bool {
+    match locale {
+        Some(value) => {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        }
+        None => true,
+    }
+}
+
+/// Detect a non-UTF-8 locale from LC_ALL, LC_CTYPE and LANG (in that order)
+pub fn detect_plain() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty());
+    !is_utf8_locale(locale.as_deref())
+}
+
+fn pick(unicode: &'static str, ascii: &'static str) -> &'static str {
+    if is_plain() {
+        ascii
+    } else {
+        unicode
+    }
+}
+
+pub fn success() -> &'static str {
+    pick("✓", "[OK]")
+}
+
+pub fn failure() -> &'static str {
+    pick("✗", "[X]")
+}
+
+pub fn warning() -> &'static str {
+    pick("⚠", "[!]")
+}
+
+pub fn cancelled() -> &'static str {
+    pick("⊘", "[CANCELLED]")
+}
+
+pub fn processing() -> &'static str {
+    pick("⟳", "[RUNNING]")
+}
+
+pub fn queued() -> &'static str {
+    pick("○", "[QUEUED]")
+}
+
+pub fn paused() -> &'static str {
+    pick("⏸", "[PAUSED]")
+}
+
+pub fn resumed() -> &'static str {
+    pick("▶", "[ACTIVE]")
+}
+
+pub fn triggered() -> &'static str {
+    pick("⚡", "[RUN]")
+}
+
+pub fn bullet() -> &'static str {
+    pick("•", "*")
+}
+
+/// Horizontal rule used around summaries
+pub fn rule(width: usize) -> String {
+    pick("═", "=").repeat(width)
+}
+
+/// Filled and empty cells for quota bars
+pub fn bar_cells() -> (&'static str, &'static str) {
+    (pick("█", "#"), pick("░", "-"))
+}
+
+/// Sparkline levels from lowest to highest
+pub fn sparkline_levels() -> [char; 9] {
+    if is_plain() {
+        [' ', '.', ':', '-', '=', '+', '*', '%', '#']
+    } else {
+        [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█']
+    }
+}
+
+/// Fill characters for indicatif progress bars
+pub fn progress_chars() -> &'static str {
+    pick("━━╺", "=>-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_utf8_locale() {
+        assert!(is_utf8_locale(Some("en_US.UTF-8")));
+        assert!(is_utf8_locale(Some("C.utf8")));
+        assert!(is_utf8_locale(None));
+        assert!(!is_utf8_locale(Some("C")));
+        assert!(!is_utf8_locale(Some("en_US.ISO-8859-1")));
+    }
+}
@@ -1,8 +1,41 @@
 use crate::error::{CliError, CliResult};
 use image::DynamicImage;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicU32, Ordering};
 use viuer::{print_from_file, Config as ViuerConfig};
 
+/// Cap on rendered image width in columns; 0 means no cap
+static MAX_WIDTH: AtomicU32 = AtomicU32::new(0);
+
+/// Cap the rendered width regardless of terminal size (see `--display-max-width`)
+pub fn set_max_width(max_width: Option<u32>) {
+    MAX_WIDTH.store(max_width.unwrap_or(0), Ordering::Relaxed);
+}
+
+fn max_width() -> Option<u32> {
+    match MAX_WIDTH.load(Ordering::Relaxed) {
+        0 => None,
+        width => Some(width),
+    }
+}
+
+/// Terminal width in columns, if stdout is a terminal
+fn terminal_width() -> Option<u16> {
+    console::Term::stdout().size_checked().map(|(_, cols)| cols)
+}
+
+/// Shrink the requested width to fit the terminal and the configured cap
+fn fit_width(requested: u32, terminal: Option<u16>, max: Option<u32>) -> u32 {
+    let mut width = requested;
+    if let Some(cols) = terminal {
+        width = width.min(cols as u32);
+    }
+    if let Some(max) = max {
+        width = width.min(max);
+    }
+    width.max(1)
+}
+
 /// Terminal image display using viuer
 pub struct TerminalImage {
     config: ViuerConfig,
@@ -15,14 +48,18 @@ impl Default for TerminalImage {
 }
 
 impl TerminalImage {
+    /// Create a display sized to the terminal width
     pub fn new() -> Self {
+        let terminal = terminal_width();
+        let width = fit_width(terminal.map_or(80, u32::from), terminal, max_width());
+
         Self {
             config: ViuerConfig {
                 absolute_offset: false,
                 x: 0,
                 y: 0,
                 restore_cursor: false,
-                width: Some(80),
+                width: Some(width),
                 height: Some(24),
                 truecolor: true,
                 use_kitty: true,
@@ -32,10 +69,10 @@ impl TerminalImage {
         }
     }
 
-    /// Create with custom dimensions
+    /// Create with custom dimensions (still subject to the max width cap)
     pub fn with_size(width: u32, height: u32) -> Self {
         let mut display = Self::new();
-        display.config.width = Some(width);
+        display.config.width = Some(fit_width(width, None, max_width()));
         display.config.height = Some(height);
         display
     }
@@ -105,6 +142,7 @@ impl TerminalImage {
     }
 
     /// Print info about the detected terminal protocol
+    #[allow(dead_code)]
     pub fn print_protocol_info() {
         let protocol = Self::detect_protocol();
         println!(
@@ -131,6 +169,14 @@ mod tests {
         assert!(["kitty", "iterm", "sixel", "blocks"].contains(&protocol));
     }
 
+    #[test]
+    fn test_fit_width() {
+        assert_eq!(fit_width(200, Some(300), Some(120)), 120);
+        assert_eq!(fit_width(200, Some(60), Some(120)), 60);
+        assert_eq!(fit_width(80, None, None), 80);
+        assert_eq!(fit_width(80, Some(0), None), 1);
+    }
+
     #[test]
     fn test_with_size() {
         let display = TerminalImage::with_size(120, 40);
@@ -0,0 +1,99 @@
+use colored::Colorize;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Wrap `text` in an OSC 8 hyperlink to `target` when the terminal can show one
+pub fn hyperlink(target: &str, text: impl Display) -> String {
+    if enabled() {
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", target, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Underlined, clickable URL for pointers like the dashboard pages
+pub fn link_url(url: &str) -> String {
+    hyperlink(url, url.cyan().underline())
+}
+
+/// Hyperlink a saved file through a file:// URL
+pub fn file_hyperlink(path: &Path, text: impl Display) -> String {
+    let absolute = if path.is_absolute() {
+        Some(path.to_path_buf())
+    } else {
+        std::env::current_dir().ok().map(|dir| dir.join(path))
+    };
+    let target = absolute.and_then(|absolute| url::Url::from_file_path(absolute).ok());
+    match target {
+        Some(target) => hyperlink(target.as_str(), text),
+        None => text.to_string(),
+    }
+}
+
+/// Links follow color: off with --no-color, NO_COLOR or a redirected stdout
+fn enabled() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    colored::control::SHOULD_COLORIZE.should_colorize()
+        && console::Term::stdout().is_term()
+        && *SUPPORTED.get_or_init(|| supports_hyperlinks(|var| std::env::var(var).ok()))
+}
+
+/// Recognize terminals known to render OSC 8; FORCE_HYPERLINK=1/0 overrides the guess
+fn supports_hyperlinks(env: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(force) = env("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+
+    let term = env("TERM").unwrap_or_default();
+    if term == "dumb" {
+        return false;
+    }
+
+    // VTE-based terminals (GNOME Terminal, Tilix, ...) since 0.50
+    if env("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()).is_some_and(|v| v >= 5000) {
+        return true;
+    }
+
+    let known_program = env("TERM_PROGRAM").is_some_and(|program| {
+        matches!(program.as_str(), "iTerm.app" | "WezTerm" | "vscode" | "Hyper" | "ghostty")
+    });
+    let known_term = ["xterm-kitty", "alacritty", "foot", "wezterm", "xterm-ghostty"]
+        .iter()
+        .any(|name| term.starts_with(name));
+
+    known_program
+        || known_term
+        || ["WT_SESSION", "KONSOLE_VERSION", "KITTY_WINDOW_ID", "DOMTERM"]
+            .iter()
+            .any(|var| env(var).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_supports_hyperlinks() {
+        assert!(supports_hyperlinks(env(&[("TERM_PROGRAM", "iTerm.app")])));
+        assert!(supports_hyperlinks(env(&[("TERM", "xterm-kitty")])));
+        assert!(supports_hyperlinks(env(&[("VTE_VERSION", "7200")])));
+        assert!(!supports_hyperlinks(env(&[("VTE_VERSION", "4600")])));
+        assert!(!supports_hyperlinks(env(&[("TERM", "xterm-256color")])));
+        assert!(!supports_hyperlinks(env(&[("TERM", "dumb"), ("WT_SESSION", "1")])));
+    }
+
+    #[test]
+    fn test_force_hyperlink() {
+        assert!(supports_hyperlinks(env(&[("FORCE_HYPERLINK", "1"), ("TERM", "dumb")])));
+        assert!(!supports_hyperlinks(env(&[("FORCE_HYPERLINK", "0"), ("TERM_PROGRAM", "vscode")])));
+    }
+}
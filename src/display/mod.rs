@@ -1,7 +1,15 @@
 mod image;
 mod progress;
 mod graphs;
+mod links;
+mod table;
+pub mod icons;
 
-pub use image::TerminalImage;
-pub use progress::{create_spinner, create_progress_bar, SpinnerStyle};
+pub use image::{set_max_width, TerminalImage};
+pub use progress::{
+    create_progress_bar, create_rate_progress_bar, create_spinner, set_no_spinner, show_percent,
+    RateEta,
+};
 pub use graphs::UsageGraph;
+pub use links::{file_hyperlink, hyperlink, link_url};
+pub use table::{set_no_borders, Table};
@@ -1,7 +1,12 @@
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use std::time::Duration;
+use super::icons;
+use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Spinner style presets
+#[allow(dead_code)]
 pub enum SpinnerStyle {
     Dots,
     Braille,
@@ -20,14 +25,53 @@ impl SpinnerStyle {
     }
 }
 
+static NO_SPINNER: AtomicBool = AtomicBool::new(false);
+
+/// Hide every spinner and progress bar, keeping all other output (see `--no-spinner`)
+pub fn set_no_spinner(no_spinner: bool) {
+    NO_SPINNER.store(no_spinner, Ordering::Relaxed);
+}
+
+/// Check whether progress output should be hidden for the given terminal
+fn should_hide(term: Option<&str>, is_tty: bool) -> bool {
+    !is_tty || term == Some("dumb")
+}
+
+/// Hide spinners and bars on dumb terminals and when stderr isn't a TTY,
+/// so CI logs don't fill up with control sequences
+fn hide_progress() -> bool {
+    if NO_SPINNER.load(Ordering::Relaxed) {
+        return true;
+    }
+    let term = std::env::var("TERM").ok();
+    should_hide(term.as_deref(), std::io::stderr().is_terminal())
+}
+
+/// Create a bar that tracks state but only draws on interactive terminals
+fn new_bar(len: Option<u64>) -> ProgressBar {
+    let pb = match len {
+        Some(len) => ProgressBar::new(len),
+        None => ProgressBar::new_spinner(),
+    };
+    if hide_progress() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    pb
+}
+
 /// Create a spinner for single operations
 pub fn create_spinner(message: &str) -> ProgressBar {
-    create_spinner_with_style(message, SpinnerStyle::Dots)
+    let style = if icons::is_plain() {
+        SpinnerStyle::Line
+    } else {
+        SpinnerStyle::Dots
+    };
+    create_spinner_with_style(message, style)
 }
 
 /// Create a spinner with a specific style
 pub fn create_spinner_with_style(message: &str, style: SpinnerStyle) -> ProgressBar {
-    let pb = ProgressBar::new_spinner();
+    let pb = new_bar(None);
     pb.set_style(
         ProgressStyle::default_spinner()
             .tick_chars(style.tick_chars())
@@ -35,42 +79,141 @@ pub fn create_spinner_with_style(message: &str, style: SpinnerStyle) -> Progress
             .unwrap(),
     );
     pb.set_message(message.to_string());
-    pb.enable_steady_tick(Duration::from_millis(80));
+    if !pb.is_hidden() {
+        pb.enable_steady_tick(Duration::from_millis(80));
+    }
     pb
 }
 
+/// Bar layout shared by the counted progress bars
+const BAR_TEMPLATE: &str = "{msg}\n{bar:40.cyan/blue} {pos}/{len} [{elapsed_precise}] ETA: {eta}";
+
 /// Create a progress bar for operations with known length
 pub fn create_progress_bar(total: u64, message: &str) -> ProgressBar {
-    let pb = ProgressBar::new(total);
+    let pb = new_bar(Some(total));
     pb.set_style(
         ProgressStyle::default_bar()
-            .template("{msg}\n{bar:40.cyan/blue} {pos}/{len} [{elapsed_precise}] ETA: {eta}")
+            .template(BAR_TEMPLATE)
             .unwrap()
-            .progress_chars("━━╺"),
+            .progress_chars(icons::progress_chars()),
     );
     pb.set_message(message.to_string());
     pb
 }
 
-/// Create a progress bar with percentage display
-pub fn create_percent_bar(message: &str) -> ProgressBar {
-    let pb = ProgressBar::new(100);
+/// Create a progress bar whose ETA comes from `eta` once it has enough samples
+pub fn create_rate_progress_bar(total: u64, message: &str, eta: &RateEta) -> ProgressBar {
+    let pb = new_bar(Some(total));
+    let eta = eta.clone();
     pb.set_style(
         ProgressStyle::default_bar()
-            .template("{msg}\n{bar:40.cyan/blue} {percent}% [{elapsed_precise}]")
+            .template(BAR_TEMPLATE)
             .unwrap()
-            .progress_chars("━━╺"),
+            .progress_chars(icons::progress_chars())
+            .with_key("eta", move |state: &ProgressState, w: &mut dyn std::fmt::Write| {
+                let remaining = state.len().unwrap_or(0).saturating_sub(state.pos());
+                let eta = eta.eta(remaining, Instant::now()).unwrap_or_else(|| state.eta());
+                let _ = write!(w, "{:#}", HumanDuration(eta));
+            }),
     );
     pb.set_message(message.to_string());
     pb
 }
 
+/// How quickly items actually complete, for an ETA that ignores idle poll ticks
+#[derive(Debug, Clone)]
+pub struct RateEta {
+    state: Arc<Mutex<RateState>>,
+}
+
+#[derive(Debug)]
+struct RateState {
+    /// Time and count of the last observed completion
+    last: (Instant, u64),
+    /// Exponentially smoothed seconds per completed item
+    secs_per_item: Option<f64>,
+}
+
+impl RateEta {
+    /// Weight of the newest sample in the smoothed rate
+    const SMOOTHING: f64 = 0.3;
+
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    fn starting_at(start: Instant) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(RateState {
+                last: (start, 0),
+                secs_per_item: None,
+            })),
+        }
+    }
+
+    /// Record the completed count seen at `now`
+    pub fn observe(&self, completed: u64, now: Instant) {
+        let Ok(mut state) = self.state.lock() else { return };
+        let (last_at, last_count) = state.last;
+        if completed <= last_count {
+            return;
+        }
+
+        let sample = now.duration_since(last_at).as_secs_f64() / (completed - last_count) as f64;
+        state.secs_per_item = Some(match state.secs_per_item {
+            Some(rate) => rate + Self::SMOOTHING * (sample - rate),
+            None => sample,
+        });
+        state.last = (now, completed);
+    }
+
+    /// Time left for `remaining` items; None until at least two have completed
+    pub fn eta(&self, remaining: u64, now: Instant) -> Option<Duration> {
+        let state = self.state.lock().ok()?;
+        let (last_at, last_count) = state.last;
+        if last_count < 2 {
+            return None;
+        }
+        let total = state.secs_per_item? * remaining as f64;
+        let since_last = now.duration_since(last_at).as_secs_f64();
+        Some(Duration::from_secs_f64((total - since_last).max(0.0)))
+    }
+}
+
+fn percent_style() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template("{msg}\n{bar:40.cyan/blue} {percent}% [{elapsed_precise}]")
+        .unwrap()
+        .progress_chars(icons::progress_chars())
+}
+
+/// Create a progress bar with percentage display
+#[allow(dead_code)]
+pub fn create_percent_bar(message: &str) -> ProgressBar {
+    let pb = new_bar(Some(100));
+    pb.set_style(percent_style());
+    pb.set_message(message.to_string());
+    pb
+}
+
+/// Turn a spinner into a percentage bar once the work reports how far along it is
+pub fn show_percent(pb: &ProgressBar, percent: u64) {
+    if pb.length() != Some(100) {
+        pb.disable_steady_tick();
+        pb.set_style(percent_style());
+        pb.set_length(100);
+    }
+    pb.set_position(percent.min(100));
+}
+
 /// Create a multi-progress for concurrent operations
+#[allow(dead_code)]
 pub fn create_multi_progress() -> MultiProgress {
     MultiProgress::new()
 }
 
 /// Spinner messages for different operations
+#[allow(dead_code)]
 pub mod messages {
     pub const CAPTURING: &str = "Capturing screenshot...";
     pub const PROCESSING: &str = "Processing...";
@@ -82,11 +225,13 @@ pub mod messages {
 }
 
 /// Progress bar helper for batch operations
+#[allow(dead_code)]
 pub struct BatchProgress {
     multi: MultiProgress,
     main_bar: ProgressBar,
 }
 
+#[allow(dead_code)]
 impl BatchProgress {
     pub fn new(total: u64, message: &str) -> Self {
         let multi = MultiProgress::new();
@@ -127,6 +272,39 @@ mod tests {
         assert_eq!(spinner.message(), "Test message");
     }
 
+    #[test]
+    fn test_should_hide() {
+        assert!(should_hide(Some("dumb"), true));
+        assert!(should_hide(Some("xterm-256color"), false));
+        assert!(should_hide(None, false));
+        assert!(!should_hide(Some("xterm-256color"), true));
+        assert!(!should_hide(None, true));
+    }
+
+    #[test]
+    fn test_rate_eta() {
+        let start = Instant::now();
+        let eta = RateEta::starting_at(start);
+
+        // One completion isn't enough to trust a rate
+        eta.observe(1, start + Duration::from_secs(10));
+        assert_eq!(eta.eta(9, start + Duration::from_secs(10)), None);
+
+        // Polls with no new completions don't move the rate
+        eta.observe(1, start + Duration::from_secs(12));
+        eta.observe(3, start + Duration::from_secs(30));
+        assert_eq!(
+            eta.eta(7, start + Duration::from_secs(30)),
+            Some(Duration::from_secs(70))
+        );
+
+        // Time since the last completion counts against the estimate
+        assert_eq!(
+            eta.eta(7, start + Duration::from_secs(40)),
+            Some(Duration::from_secs(60))
+        );
+    }
+
     #[test]
     fn test_create_progress_bar() {
         let bar = create_progress_bar(100, "Test");
@@ -0,0 +1,197 @@
+use super::icons;
+use colored::Colorize;
+use console::measure_text_width;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static NO_BORDERS: AtomicBool = AtomicBool::new(false);
+
+/// Render tables as plain space-separated columns (see `--no-borders`)
+pub fn set_no_borders(no_borders: bool) {
+    NO_BORDERS.store(no_borders, Ordering::Relaxed);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Unicode,
+    Ascii,
+    None,
+}
+
+impl Style {
+    fn current() -> Self {
+        if NO_BORDERS.load(Ordering::Relaxed) {
+            Style::None
+        } else if icons::is_plain() {
+            Style::Ascii
+        } else {
+            Style::Unicode
+        }
+    }
+}
+
+/// Column-aligned table; widths count terminal columns, so colors and CJK line up
+#[derive(Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    right: Vec<usize>,
+}
+
+impl Table {
+    pub fn new<S: ToString>(headers: &[S]) -> Self {
+        Self {
+            headers: headers.iter().map(ToString::to_string).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Right-align a column, e.g. for counts
+    pub fn align_right(mut self, column: usize) -> Self {
+        self.right.push(column);
+        self
+    }
+
+    pub fn add_row<S: ToString>(&mut self, cells: Vec<S>) {
+        self.rows.push(cells.iter().map(ToString::to_string).collect());
+    }
+
+    /// Format the table as printable lines in the current border style
+    pub fn render(&self) -> Vec<String> {
+        self.render_style(Style::current())
+    }
+
+    pub fn print(&self) {
+        for line in self.render() {
+            println!("{}", line);
+        }
+    }
+
+    fn render_style(&self, style: Style) -> Vec<String> {
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or(0);
+
+        let mut widths = vec![0; columns];
+        for row in self.rows.iter().chain([&self.headers]) {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(measure_text_width(cell));
+            }
+        }
+
+        let headers: Vec<String> = self.headers.iter().map(|h| h.bold().to_string()).collect();
+        let mut lines = Vec::new();
+
+        match style {
+            Style::None => {
+                lines.push(self.join_row(&headers, &widths, "", "  ", ""));
+                for row in &self.rows {
+                    lines.push(self.join_row(row, &widths, "", "  ", ""));
+                }
+            }
+            Style::Unicode | Style::Ascii => {
+                let (h, v, [top, mid, bottom]) = match style {
+                    Style::Unicode => ("─", "│", [["┌", "┬", "┐"], ["├", "┼", "┤"], ["└", "┴", "┘"]]),
+                    _ => ("-", "|", [["+", "+", "+"], ["+", "+", "+"], ["+", "+", "+"]]),
+                };
+                let rule = |[left, cross, right]: [&str; 3]| {
+                    let segments: Vec<String> = widths.iter().map(|w| h.repeat(w + 2)).collect();
+                    format!("{}{}{}", left, segments.join(cross), right)
+                };
+                let left = format!("{} ", v);
+                let sep = format!(" {} ", v);
+                let right = format!(" {}", v);
+
+                lines.push(rule(top));
+                lines.push(self.join_row(&headers, &widths, &left, &sep, &right));
+                lines.push(rule(mid));
+                for row in &self.rows {
+                    lines.push(self.join_row(row, &widths, &left, &sep, &right));
+                }
+                lines.push(rule(bottom));
+            }
+        }
+
+        lines
+    }
+
+    fn join_row(&self, row: &[String], widths: &[usize], left: &str, sep: &str, right: &str) -> String {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, &width)| {
+                let cell = row.get(i).map(String::as_str).unwrap_or("");
+                let padding = " ".repeat(width - measure_text_width(cell));
+                if self.right.contains(&i) {
+                    format!("{}{}", padding, cell)
+                } else {
+                    format!("{}{}", cell, padding)
+                }
+            })
+            .collect();
+
+        let line = format!("{}{}{}", left, cells.join(sep), right);
+        // Trailing padding only matters when a border closes the row
+        if right.is_empty() {
+            line.trim_end().to_string()
+        } else {
+            line
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Table {
+        let mut table = Table::new(&["Name", "Count"]).align_right(1);
+        table.add_row(vec!["東京", "5"]);
+        table.add_row(vec!["paris", "12"]);
+        table
+    }
+
+    #[test]
+    fn test_render_unicode() {
+        let lines: Vec<String> = sample()
+            .render_style(Style::Unicode)
+            .iter()
+            .map(|l| console::strip_ansi_codes(l).to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "┌───────┬───────┐",
+                "│ Name  │ Count │",
+                "├───────┼───────┤",
+                "│ 東京  │     5 │",
+                "│ paris │    12 │",
+                "└───────┴───────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_no_borders() {
+        let lines: Vec<String> = sample()
+            .render_style(Style::None)
+            .iter()
+            .map(|l| console::strip_ansi_codes(l).to_string())
+            .collect();
+        assert_eq!(lines, vec!["Name   Count", "東京       5", "paris     12"]);
+    }
+
+    #[test]
+    fn test_colored_cells_align() {
+        let mut table = Table::new(&["Status"]);
+        table.add_row(vec!["\x1b[32mOK\x1b[0m"]);
+        table.add_row(vec!["FAILED"]);
+        let lines = table.render_style(Style::Ascii);
+
+        let widths: Vec<usize> = lines.iter().map(|l| measure_text_width(l)).collect();
+        assert!(widths.iter().all(|&w| w == widths[0]));
+    }
+}
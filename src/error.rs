@@ -1,3 +1,4 @@
+use crate::display::link_url;
 use allscreenshots_sdk::{AllscreenshotsError, ErrorCode};
 use colored::Colorize;
 use thiserror::Error;
@@ -31,11 +32,98 @@ pub enum CliError {
     #[error("Clipboard error: {0}")]
     ClipboardError(String),
 
+    /// --poll-timeout elapsed before the job finished; `hint` says how to check on it later
+    #[error("Job did not finish within {waited}")]
+    PollTimeout { waited: String, hint: String },
+
+    /// `quota --exit-if-below` found fewer screenshots left than required
+    #[error("Only {remaining} screenshots remaining, below the required {minimum}")]
+    QuotaBelow { remaining: i32, minimum: u32 },
+
     #[error("{0}")]
     Other(String),
 }
 
+/// Exit status for failures without a more specific code
+pub const EXIT_FAILURE: i32 = 1;
+/// Invalid arguments or input, the same status clap uses for usage errors
+pub const EXIT_USAGE: i32 = 2;
+/// Missing or rejected API key
+pub const EXIT_AUTH: i32 = 3;
+/// The API couldn't be reached or didn't answer in time
+pub const EXIT_NETWORK: i32 = 4;
+/// `quota --exit-if-below` found too few screenshots left
+pub const EXIT_QUOTA_BELOW: i32 = 5;
+
 impl CliError {
+    /// Stable snake_case identifier for scripts, derived from the variant
+    pub fn code(&self) -> String {
+        match self {
+            CliError::NoApiKey | CliError::Sdk(AllscreenshotsError::EnvVarNotSet(_)) => {
+                "no_api_key".to_string()
+            }
+            CliError::Sdk(AllscreenshotsError::ApiError { code, .. }) => code.to_string().to_lowercase(),
+            CliError::Sdk(AllscreenshotsError::HttpError(e)) if e.is_timeout() => "timeout".to_string(),
+            CliError::Sdk(AllscreenshotsError::HttpError(e)) if e.is_connect() => {
+                "connection_failed".to_string()
+            }
+            CliError::Sdk(AllscreenshotsError::HttpError(_)) => "network_error".to_string(),
+            CliError::Sdk(AllscreenshotsError::ValidationError(_)) => "validation_error".to_string(),
+            CliError::Sdk(AllscreenshotsError::RetriesExhausted(_)) => "retries_exhausted".to_string(),
+            CliError::Sdk(AllscreenshotsError::Timeout) => "timeout".to_string(),
+            CliError::Sdk(_) => "sdk_error".to_string(),
+            CliError::Config(_) => "config_error".to_string(),
+            CliError::InvalidUrl(_) => "invalid_url".to_string(),
+            CliError::FileNotFound(_) => "file_not_found".to_string(),
+            CliError::FileReadError(_) => "file_read_error".to_string(),
+            CliError::FileWriteError(_) => "file_write_error".to_string(),
+            CliError::DisplayError(_) => "display_error".to_string(),
+            CliError::ClipboardError(_) => "clipboard_error".to_string(),
+            CliError::PollTimeout { .. } => "poll_timeout".to_string(),
+            CliError::QuotaBelow { .. } => "quota_below".to_string(),
+            CliError::Other(_) => "error".to_string(),
+        }
+    }
+
+    /// Process exit status, so scripts can tell the main failure classes apart
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::InvalidUrl(_)
+            | CliError::Sdk(AllscreenshotsError::ValidationError(_))
+            | CliError::Sdk(AllscreenshotsError::ApiError {
+                code: ErrorCode::ValidationError,
+                ..
+            }) => EXIT_USAGE,
+            CliError::NoApiKey
+            | CliError::Sdk(AllscreenshotsError::EnvVarNotSet(_))
+            | CliError::Sdk(AllscreenshotsError::ApiError {
+                code: ErrorCode::Unauthorized,
+                ..
+            }) => EXIT_AUTH,
+            CliError::Sdk(AllscreenshotsError::HttpError(_) | AllscreenshotsError::Timeout) => {
+                EXIT_NETWORK
+            }
+            CliError::QuotaBelow { .. } => EXIT_QUOTA_BELOW,
+            _ => EXIT_FAILURE,
+        }
+    }
+
+    /// Failures that only set the exit status, reported only with --verbose
+    pub fn is_quiet(&self) -> bool {
+        matches!(self, CliError::QuotaBelow { .. })
+    }
+
+    /// Machine-readable form printed instead of the friendly text under `--json`
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": {
+                "code": self.code(),
+                "message": self.to_string(),
+                "exit_code": self.exit_code(),
+            }
+        })
+    }
+
     /// Format the error with user-friendly messages and helpful suggestions
     pub fn format_friendly(&self) -> String {
         match self {
@@ -47,7 +135,7 @@ impl CliError {
                     "1. Set the ALLSCREENSHOTS_API_KEY environment variable",
                     "2. Run: allscreenshots config add-authtoken <your-key>",
                     "Get your API key at:".dimmed(),
-                    "https://dashboard.allscreenshots.com/api-keys".cyan().underline()
+                    link_url("https://dashboard.allscreenshots.com/api-keys")
                 )
             }
 
@@ -64,7 +152,7 @@ impl CliError {
                             "Your API key appears to be invalid.".yellow(),
                             message,
                             "Check your key at:".dimmed(),
-                            "https://dashboard.allscreenshots.com/api-keys".cyan().underline()
+                            link_url("https://dashboard.allscreenshots.com/api-keys")
                         )
                     }
                     ErrorCode::RateLimitExceeded => {
@@ -73,7 +161,7 @@ impl CliError {
                             "Rate limit exceeded!".red().bold(),
                             "You've made too many requests. Please wait a moment and try again.".yellow(),
                             "Upgrade your plan for higher limits:".dimmed(),
-                            "https://dashboard.allscreenshots.com/billing".cyan().underline()
+                            link_url("https://dashboard.allscreenshots.com/billing")
                         )
                     }
                     ErrorCode::ValidationError => {
@@ -201,6 +289,23 @@ impl CliError {
                 )
             }
 
+            CliError::PollTimeout { waited, hint } => {
+                format!(
+                    "{}\n\n{}\n  {}",
+                    "Stopped waiting!".red().bold(),
+                    format!("The job was still running after {}; it may finish later.", waited).yellow(),
+                    hint.dimmed()
+                )
+            }
+
+            CliError::QuotaBelow { .. } => {
+                format!(
+                    "{}\n\n{}",
+                    "Quota too low!".red().bold(),
+                    self.to_string().yellow()
+                )
+            }
+
             CliError::Config(e) => {
                 format!(
                     "{}\n\n{}",
@@ -231,7 +336,65 @@ impl CliError {
     pub fn print_friendly(&self) {
         eprintln!("\n{}\n", self.format_friendly());
     }
+
+    /// What the API answered for a failed request, for --dump-raw-response; None for other errors
+    pub fn api_response(&self) -> Option<serde_json::Value> {
+        match self {
+            CliError::Sdk(AllscreenshotsError::ApiError { code, message, status }) => Some(serde_json::json!({
+                "status": status,
+                "error_code": code.to_string(),
+                "message": message,
+            })),
+            _ => None,
+        }
+    }
 }
 
 /// Result type for CLI operations
 pub type CliResult<T> = Result<T, CliError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let json = CliError::FileNotFound("urls.txt".to_string()).to_json();
+        assert_eq!(json["error"]["code"], "file_not_found");
+        assert_eq!(json["error"]["message"], "File not found: urls.txt");
+        assert_eq!(json["error"]["exit_code"], EXIT_FAILURE);
+
+        assert_eq!(CliError::NoApiKey.code(), "no_api_key");
+        assert_eq!(CliError::Other("boom".to_string()).code(), "error");
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let api_error = |status, code| {
+            CliError::Sdk(AllscreenshotsError::from_api_response(status, Some(code), "nope"))
+        };
+        assert_eq!(CliError::InvalidUrl("htps://x".to_string()).exit_code(), EXIT_USAGE);
+        assert_eq!(api_error(422, "VALIDATION_ERROR").exit_code(), EXIT_USAGE);
+        assert_eq!(CliError::NoApiKey.exit_code(), EXIT_AUTH);
+        assert_eq!(api_error(401, "UNAUTHORIZED").exit_code(), EXIT_AUTH);
+        assert_eq!(CliError::Sdk(AllscreenshotsError::Timeout).exit_code(), EXIT_NETWORK);
+        let below = CliError::QuotaBelow { remaining: 3, minimum: 10 };
+        assert_eq!(below.exit_code(), EXIT_QUOTA_BELOW);
+        assert_eq!(api_error(500, "INTERNAL_ERROR").exit_code(), EXIT_FAILURE);
+    }
+
+    #[test]
+    fn test_api_response() {
+        let error = CliError::Sdk(AllscreenshotsError::from_api_response(
+            422,
+            Some("VALIDATION_ERROR"),
+            "width must be at most 3840",
+        ));
+        let response = error.api_response().unwrap();
+        assert_eq!(response["status"], 422);
+        assert_eq!(response["error_code"], "VALIDATION_ERROR");
+        assert_eq!(response["message"], "width must be at most 3840");
+
+        assert!(CliError::NoApiKey.api_response().is_none());
+    }
+}
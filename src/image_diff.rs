@@ -0,0 +1,235 @@
+use crate::error::{CliError, CliResult};
+use image::{imageops, Rgba, RgbaImage};
+
+/// Per-channel difference treated as compression noise rather than a change
+pub const TOLERANCE: u8 = 8;
+/// Space between the two panels of a side-by-side diff
+const GAP: u32 = 16;
+
+const BACKGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const HIGHLIGHT: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+/// Artifacts the diff renderer can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOutput {
+    /// Baseline and current next to each other, changes highlighted on the current side
+    SideBySide,
+    /// Both images blended at half opacity
+    Overlay,
+    /// Faded current image with changes colored yellow (slight) to red (strong)
+    Heatmap,
+    /// Only the changed pixels of the current image, everything else transparent
+    ChangedOnly,
+}
+
+impl DiffOutput {
+    /// Parse a --diff-output name
+    pub fn parse(s: &str) -> CliResult<Self> {
+        match s.to_lowercase().as_str() {
+            "side-by-side" => Ok(DiffOutput::SideBySide),
+            "overlay" => Ok(DiffOutput::Overlay),
+            "heatmap" => Ok(DiffOutput::Heatmap),
+            "changed-only" => Ok(DiffOutput::ChangedOnly),
+            _ => Err(CliError::Other(format!(
+                "Unknown diff output '{}'. Use: side-by-side, overlay, heatmap, or changed-only",
+                s
+            ))),
+        }
+    }
+}
+
+/// A rendered diff and how many pixels changed
+pub struct Diff {
+    pub image: RgbaImage,
+    pub changed: u64,
+    pub total: u64,
+}
+
+impl Diff {
+    /// Fraction of compared pixels that changed
+    pub fn changed_ratio(&self) -> f64 {
+        ratio(self.changed, self.total)
+    }
+}
+
+fn ratio(changed: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        changed as f64 / total as f64
+    }
+}
+
+/// Fraction of pixels that changed, lined up the same way as `render`
+pub fn changed_ratio(baseline: &RgbaImage, current: &RgbaImage) -> f64 {
+    let (changed, total) = compare(baseline, current, |_, _, _, _, _| {});
+    ratio(changed, total)
+}
+
+/// Render the difference between two images lined up at their top-left corners.
+///
+/// When the sizes differ both are compared on a canvas as large as the bigger of the two;
+/// area that only one image covers counts as changed.
+pub fn render(baseline: &RgbaImage, current: &RgbaImage, output: DiffOutput) -> Diff {
+    let width = baseline.width().max(current.width());
+    let height = baseline.height().max(current.height());
+
+    let mut image = match output {
+        DiffOutput::SideBySide => {
+            let mut canvas = RgbaImage::from_pixel(width * 2 + GAP, height, BACKGROUND);
+            imageops::replace(&mut canvas, baseline, 0, 0);
+            imageops::replace(&mut canvas, current, (width + GAP) as i64, 0);
+            canvas
+        }
+        _ => RgbaImage::new(width, height),
+    };
+
+    let (changed, total) = compare(baseline, current, |x, y, before, after, difference| {
+        let is_changed = difference > TOLERANCE;
+        match output {
+            DiffOutput::SideBySide => {
+                if is_changed {
+                    let pixel = image.get_pixel_mut(width + GAP + x, y);
+                    *pixel = blend(after.unwrap_or(BACKGROUND), HIGHLIGHT, 0.5);
+                }
+            }
+            DiffOutput::Overlay => {
+                let transparent = Rgba([0, 0, 0, 0]);
+                image.put_pixel(
+                    x,
+                    y,
+                    blend(before.unwrap_or(transparent), after.unwrap_or(transparent), 0.5),
+                );
+            }
+            DiffOutput::Heatmap => {
+                let pixel = if is_changed {
+                    heat(difference)
+                } else {
+                    faded(after.or(before).unwrap_or(BACKGROUND))
+                };
+                image.put_pixel(x, y, pixel);
+            }
+            DiffOutput::ChangedOnly => {
+                if is_changed {
+                    // Removed area shows what used to be there
+                    image.put_pixel(x, y, after.or(before).unwrap_or(HIGHLIGHT));
+                }
+            }
+        }
+    });
+
+    Diff {
+        image,
+        changed,
+        total,
+    }
+}
+
+/// Walk the union of both images, top-left aligned, handing each pixel pair and its
+/// difference to `visit`; returns the changed and total pixel counts
+fn compare(
+    baseline: &RgbaImage,
+    current: &RgbaImage,
+    mut visit: impl FnMut(u32, u32, Option<Rgba<u8>>, Option<Rgba<u8>>, u8),
+) -> (u64, u64) {
+    let width = baseline.width().max(current.width());
+    let height = baseline.height().max(current.height());
+
+    let mut changed = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let before = baseline.get_pixel_checked(x, y).copied();
+            let after = current.get_pixel_checked(x, y).copied();
+            let difference = difference(before, after);
+            if difference > TOLERANCE {
+                changed += 1;
+            }
+            visit(x, y, before, after, difference);
+        }
+    }
+
+    (changed, width as u64 * height as u64)
+}
+
+/// Largest per-channel difference; a pixel only one image has is fully changed
+fn difference(before: Option<Rgba<u8>>, after: Option<Rgba<u8>>) -> u8 {
+    match (before, after) {
+        (Some(a), Some(b)) => a.0.iter().zip(b.0.iter()).map(|(x, y)| x.abs_diff(*y)).max().unwrap_or(0),
+        (None, None) => 0,
+        _ => u8::MAX,
+    }
+}
+
+fn blend(a: Rgba<u8>, b: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    let mix = |x: u8, y: u8| (x as f32 * (1.0 - amount) + y as f32 * amount).round() as u8;
+    Rgba([mix(a[0], b[0]), mix(a[1], b[1]), mix(a[2], b[2]), mix(a[3], b[3])])
+}
+
+/// Yellow for barely changed pixels through to red for completely different ones
+fn heat(difference: u8) -> Rgba<u8> {
+    Rgba([255, u8::MAX - difference, 0, 255])
+}
+
+/// Light grayscale so the highlighted changes stand out
+fn faded(pixel: Rgba<u8>) -> Rgba<u8> {
+    let luma = (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) as u8;
+    let light = u8::MAX - (u8::MAX - luma) / 4;
+    Rgba([light, light, light, 255])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba(color))
+    }
+
+    #[test]
+    fn test_parse_diff_output() {
+        assert_eq!(DiffOutput::parse("Side-By-Side").unwrap(), DiffOutput::SideBySide);
+        assert_eq!(DiffOutput::parse("changed-only").unwrap(), DiffOutput::ChangedOnly);
+        assert!(DiffOutput::parse("sparkle").is_err());
+    }
+
+    #[test]
+    fn test_render_counts_changes() {
+        let baseline = image(4, 4, [255, 255, 255, 255]);
+        let mut current = baseline.clone();
+        current.put_pixel(0, 0, Rgba([0, 0, 0, 255]));
+        // Compression noise below the tolerance
+        current.put_pixel(1, 0, Rgba([250, 250, 250, 255]));
+
+        let diff = render(&baseline, &current, DiffOutput::Heatmap);
+        assert_eq!((diff.changed, diff.total), (1, 16));
+        assert_eq!(diff.image.dimensions(), (4, 4));
+        assert_eq!(*diff.image.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+
+        let side_by_side = render(&baseline, &current, DiffOutput::SideBySide);
+        assert_eq!(side_by_side.image.dimensions(), (4 * 2 + GAP, 4));
+        assert_eq!(*side_by_side.image.get_pixel(1, 0), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_render_different_sizes() {
+        let baseline = image(4, 2, [0, 0, 255, 255]);
+        let current = image(4, 4, [0, 0, 255, 255]);
+
+        let diff = render(&baseline, &current, DiffOutput::ChangedOnly);
+        assert_eq!(diff.image.dimensions(), (4, 4));
+        assert_eq!(diff.changed, 8);
+        assert_eq!(diff.changed_ratio(), 0.5);
+        assert_eq!(diff.image.get_pixel(0, 0)[3], 0);
+        assert_eq!(*diff.image.get_pixel(0, 3), Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_overlay_blends() {
+        let diff = render(
+            &image(1, 1, [0, 0, 0, 255]),
+            &image(1, 1, [255, 255, 255, 255]),
+            DiffOutput::Overlay,
+        );
+        assert_eq!(*diff.image.get_pixel(0, 0), Rgba([128, 128, 128, 255]));
+    }
+}
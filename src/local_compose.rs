@@ -0,0 +1,245 @@
+use crate::error::{CliError, CliResult};
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use rayon::prelude::*;
+use std::path::PathBuf;
+
+/// Layouts that can be composed locally
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalLayout {
+    Grid,
+    Horizontal,
+    Vertical,
+}
+
+impl LocalLayout {
+    /// Parse a layout name, rejecting server-only layouts
+    pub fn parse(s: &str) -> CliResult<Self> {
+        match s.to_lowercase().as_str() {
+            "grid" | "auto" => Ok(LocalLayout::Grid),
+            "horizontal" => Ok(LocalLayout::Horizontal),
+            "vertical" => Ok(LocalLayout::Vertical),
+            _ => Err(CliError::Other(format!(
+                "Layout '{}' isn't available for local composition. Use: grid, horizontal, vertical, or auto",
+                s
+            ))),
+        }
+    }
+}
+
+/// Options for composing images on a single canvas
+#[derive(Debug, Clone)]
+pub struct ComposeOptions {
+    pub layout: LocalLayout,
+    pub columns: Option<u32>,
+    pub spacing: u32,
+    pub padding: u32,
+    pub background: Rgba<u8>,
+}
+
+impl Default for ComposeOptions {
+    fn default() -> Self {
+        Self {
+            layout: LocalLayout::Grid,
+            columns: None,
+            spacing: 0,
+            padding: 0,
+            background: Rgba([255, 255, 255, 255]),
+        }
+    }
+}
+
+/// Parse a background color: "#RRGGBB" or "transparent"
+pub fn parse_background(s: &str) -> CliResult<Rgba<u8>> {
+    if s.eq_ignore_ascii_case("transparent") {
+        return Ok(Rgba([0, 0, 0, 0]));
+    }
+
+    let hex = s.trim_start_matches('#');
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok(Rgba([r, g, b, 255])),
+        _ => Err(CliError::Other(format!(
+            "Invalid background '{}'. Use #RRGGBB or \"transparent\"",
+            s
+        ))),
+    }
+}
+
+/// Decode image files in parallel, keeping the input order.
+///
+/// `threads` bounds the worker pool; `None` uses one thread per core.
+pub fn decode_images(paths: &[PathBuf], threads: Option<usize>) -> CliResult<Vec<DynamicImage>> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or(0))
+        .build()
+        .map_err(|e| CliError::Other(format!("Failed to start decoder threads: {}", e)))?;
+
+    pool.install(|| {
+        paths
+            .par_iter()
+            .map(|path| {
+                if !path.exists() {
+                    return Err(CliError::FileNotFound(path.display().to_string()));
+                }
+                image::open(path)
+                    .map_err(|e| CliError::FileReadError(format!("{}: {}", path.display(), e)))
+            })
+            .collect()
+    })
+}
+
+/// Decode in-memory images such as downloaded job results, keeping the input order
+pub fn decode_bytes(buffers: &[Vec<u8>]) -> CliResult<Vec<DynamicImage>> {
+    buffers
+        .par_iter()
+        .enumerate()
+        .map(|(i, bytes)| {
+            image::load_from_memory(bytes)
+                .map_err(|e| CliError::Other(format!("Failed to decode image {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+/// Place images on one canvas with every cell sized to the largest image
+pub fn compose(images: &[DynamicImage], options: &ComposeOptions) -> RgbaImage {
+    let count = images.len().max(1) as u32;
+    let columns = match options.layout {
+        LocalLayout::Horizontal => count,
+        LocalLayout::Vertical => 1,
+        LocalLayout::Grid => options
+            .columns
+            .unwrap_or_else(|| (count as f64).sqrt().ceil() as u32)
+            .clamp(1, count),
+    };
+    let rows = (count + columns - 1) / columns;
+
+    let cell_width = images.iter().map(|img| img.width()).max().unwrap_or(0);
+    let cell_height = images.iter().map(|img| img.height()).max().unwrap_or(0);
+
+    let span = |cells: u32, size: u32| {
+        cells * size + (cells - 1) * options.spacing + 2 * options.padding
+    };
+    let mut canvas = RgbaImage::from_pixel(
+        span(columns, cell_width),
+        span(rows, cell_height),
+        options.background,
+    );
+
+    for (i, img) in images.iter().enumerate() {
+        let (col, row) = (i as u32 % columns, i as u32 / columns);
+        let x = options.padding + col * (cell_width + options.spacing);
+        let y = options.padding + row * (cell_height + options.spacing);
+        imageops::overlay(&mut canvas, &img.to_rgba8(), x as i64, y as i64);
+    }
+
+    canvas
+}
+
+/// Stack viewport shots taken at increasing pixel offsets, dropping the rows each one
+/// shares with the shot above it
+pub fn stitch_vertical(frames: &[DynamicImage], offsets: &[u32]) -> RgbaImage {
+    // Bottom edge of the page covered so far
+    let mut covered = 0u32;
+    let mut parts = Vec::new();
+    for (frame, &offset) in frames.iter().zip(offsets) {
+        let skip = covered.saturating_sub(offset).min(frame.height());
+        if skip < frame.height() {
+            parts.push(frame.crop_imm(0, skip, frame.width(), frame.height() - skip));
+        }
+        covered = covered.max(offset + frame.height());
+    }
+
+    let width = parts.iter().map(|part| part.width()).max().unwrap_or(0);
+    let height = parts.iter().map(|part| part.height()).sum();
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]));
+    let mut y = 0;
+    for part in &parts {
+        imageops::overlay(&mut canvas, &part.to_rgba8(), 0, y as i64);
+        y += part.height();
+    }
+
+    canvas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255])))
+    }
+
+    #[test]
+    fn test_parse_background() {
+        assert_eq!(parse_background("#ff0080").unwrap(), Rgba([255, 0, 128, 255]));
+        assert_eq!(parse_background("transparent").unwrap(), Rgba([0, 0, 0, 0]));
+        assert!(parse_background("#fff").is_err());
+        assert!(parse_background("red").is_err());
+    }
+
+    #[test]
+    fn test_decode_bytes() {
+        let encode = |img: DynamicImage| {
+            let mut bytes = Vec::new();
+            img.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+                .unwrap();
+            bytes
+        };
+        let buffers = vec![encode(solid(3, 2, 1)), encode(solid(5, 4, 2))];
+
+        let images = decode_bytes(&buffers).unwrap();
+        assert_eq!(images[0].width(), 3);
+        assert_eq!(images[1].width(), 5);
+        assert!(decode_bytes(&[b"not an image".to_vec()]).is_err());
+    }
+
+    #[test]
+    fn test_compose_grid() {
+        let images = vec![solid(10, 10, 1), solid(10, 10, 2), solid(10, 10, 3)];
+        let options = ComposeOptions {
+            spacing: 2,
+            padding: 1,
+            ..Default::default()
+        };
+
+        let canvas = compose(&images, &options);
+        // 2 columns x 2 rows of 10px cells
+        assert_eq!(canvas.dimensions(), (24, 24));
+        // Images keep their input order: left to right, then top to bottom
+        assert_eq!(canvas.get_pixel(1, 1).0[0], 1);
+        assert_eq!(canvas.get_pixel(13, 1).0[0], 2);
+        assert_eq!(canvas.get_pixel(1, 13).0[0], 3);
+        assert_eq!(canvas.get_pixel(13, 13).0[0], 255);
+    }
+
+    #[test]
+    fn test_compose_horizontal_and_vertical() {
+        let images = vec![solid(10, 5, 1), solid(4, 8, 2)];
+        let row = ComposeOptions {
+            layout: LocalLayout::Horizontal,
+            ..Default::default()
+        };
+        let column = ComposeOptions {
+            layout: LocalLayout::Vertical,
+            ..Default::default()
+        };
+
+        assert_eq!(compose(&images, &row).dimensions(), (20, 8));
+        assert_eq!(compose(&images, &column).dimensions(), (10, 16));
+    }
+
+    #[test]
+    fn test_stitch_vertical() {
+        let frames = vec![solid(10, 100, 1), solid(10, 100, 2), solid(10, 100, 3)];
+
+        // Overlapping offsets keep only the new rows of each shot
+        let stitched = stitch_vertical(&frames, &[0, 60, 300]);
+        assert_eq!(stitched.dimensions(), (10, 260));
+        assert_eq!(stitched.get_pixel(0, 159)[0], 2);
+        assert_eq!(stitched.get_pixel(0, 160)[0], 3);
+
+        // A shot entirely inside an earlier one adds nothing
+        assert_eq!(stitch_vertical(&frames[..2], &[0, 0]).dimensions(), (10, 100));
+    }
+}
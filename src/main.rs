@@ -2,13 +2,25 @@ use clap::{Parser, Subcommand};
 use colored::Colorize;
 use std::path::PathBuf;
 
+mod annotate;
+mod archive;
 mod commands;
 mod config;
+mod context;
 mod display;
 mod error;
+mod image_diff;
+mod local_compose;
+mod manifest;
+mod metadata;
+mod pdf_merge;
+mod rate_limit;
+mod retry;
 mod utils;
 
 use config::Config;
+use context::Context;
+use display::link_url;
 use error::{CliError, CliResult};
 
 #[derive(Parser)]
@@ -48,6 +60,10 @@ struct Cli {
     #[arg(short = 'k', long, global = true, env = "ALLSCREENSHOTS_API_KEY")]
     api_key: Option<String>,
 
+    /// API base URL for staging or self-hosted instances
+    #[arg(long, global = true, env = "ALLSCREENSHOTS_API_BASE", value_name = "URL")]
+    api_base: Option<String>,
+
     /// Output file path
     #[arg(short, long, global = true)]
     output: Option<PathBuf>,
@@ -80,6 +96,46 @@ struct Cli {
     #[arg(long, global = true)]
     no_color: bool,
 
+    /// ASCII-only icons and bars (auto-enabled on non-UTF-8 locales)
+    #[arg(long, global = true)]
+    plain: bool,
+
+    /// Print tables as space-separated columns without borders
+    #[arg(long, global = true)]
+    no_borders: bool,
+
+    /// Hide spinners and progress bars but keep all other output
+    #[arg(long, global = true)]
+    no_spinner: bool,
+
+    /// Permissions for saved screenshots, e.g. 644 (Unix; directories get matching execute bits)
+    #[arg(long, global = true, value_name = "MODE")]
+    chmod: Option<String>,
+
+    /// Don't create missing output directories (asks first on a terminal)
+    #[arg(long, global = true)]
+    no_create_dirs: bool,
+
+    /// File name template for saved screenshots, e.g. "{index}_{domain}.{format}"
+    #[arg(long, global = true, value_name = "TEMPLATE")]
+    output_template: Option<String>,
+
+    /// Cap the width of images shown in the terminal, in columns
+    #[arg(long, global = true, value_name = "COLS")]
+    display_max_width: Option<u32>,
+
+    /// Require URLs with an explicit http:// or https:// instead of adding https://
+    #[arg(long, global = true)]
+    strict_url: bool,
+
+    /// Retry failed requests up to N times (default 2; 0 disables retries)
+    #[arg(long, global = true, value_name = "N")]
+    retries: Option<u32>,
+
+    /// When the API rejects a request, write its status and error payload to PATH for bug reports
+    #[arg(long, global = true, value_name = "PATH")]
+    dump_raw_response: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -87,7 +143,7 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Take a synchronous screenshot
-    Capture(commands::capture::CaptureArgs),
+    Capture(Box<commands::capture::CaptureArgs>),
 
     /// Take an async screenshot with job tracking
     Async(commands::async_capture::AsyncArgs),
@@ -98,12 +154,21 @@ enum Commands {
     /// Combine multiple screenshots into one image
     Compose(commands::compose::ComposeArgs),
 
+    /// Compare two images and render where they differ
+    Diff(commands::diff::DiffArgs),
+
+    /// Capture several URLs as PDF and merge them into one document
+    Pdf(commands::pdf::PdfArgs),
+
     /// Manage scheduled screenshots
     Schedule(commands::schedule::ScheduleCommand),
 
     /// Show API usage and quota
     Usage(commands::usage::UsageArgs),
 
+    /// Show remaining screenshot quota (--exit-if-below for script pre-flight checks)
+    Quota(commands::usage::QuotaArgs),
+
     /// Manage authentication and settings
     Config(commands::config::ConfigCommand),
 
@@ -127,8 +192,28 @@ enum Commands {
         instructions: bool,
     },
 
-    /// Show available device presets
+    /// Show available device presets (--json for a machine-readable list)
     Devices,
+
+    /// Show supported formats and what each supports (--json for a machine-readable list)
+    Formats,
+
+    /// Show capture metadata embedded in a saved screenshot
+    Metadata(commands::metadata::MetadataArgs),
+
+    /// Check API key, config, output directory and terminal support
+    Doctor,
+
+    /// Print shell lines that export the API key for other tools
+    Env(commands::env::EnvArgs),
+
+    /// Show the CLI version (with --verbose: SDK, commit, target and features)
+    Version,
+
+    /// Update to the latest release from GitHub
+    #[cfg(feature = "self-update")]
+    #[command(name = "self-update")]
+    SelfUpdate(commands::update::UpdateArgs),
 }
 
 #[tokio::main]
@@ -140,10 +225,70 @@ async fn main() {
         colored::control::set_override(false);
     }
 
-    // Run the CLI
+    // Fall back to ASCII where Unicode icons would render as boxes
+    display::icons::set_plain(cli.plain || display::icons::detect_plain());
+    display::set_no_borders(cli.no_borders);
+    display::set_no_spinner(cli.no_spinner);
+
+    // Run the CLI; --json callers get the failure as JSON on stderr too
+    let json = cli.json;
+    let verbose = cli.verbose;
+    let dump = cli.dump_raw_response.clone().map(|path| (path, cli.api_key.clone()));
     if let Err(e) = run(cli).await {
-        e.print_friendly();
-        std::process::exit(1);
+        if let Some((ref path, ref cli_key)) = dump {
+            dump_raw_response(&e, path, cli_key.as_deref());
+        }
+        if e.is_quiet() && !verbose {
+            // Only the exit status reports it
+        } else if json {
+            eprintln!("{}", e.to_json());
+        } else {
+            e.print_friendly();
+        }
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// Save the API's answer for a failed request with the command line, keys masked
+fn dump_raw_response(e: &CliError, path: &std::path::Path, cli_key: Option<&str>) {
+    let Some(response) = e.api_response() else {
+        return;
+    };
+
+    let secrets: Vec<String> = [
+        cli_key.map(str::to_string),
+        std::env::var("ALLSCREENSHOTS_API_KEY").ok(),
+        Config::load().ok().and_then(|config| config.auth.api_key),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let args: Vec<String> = std::env::args().collect();
+
+    let dump = serde_json::json!({
+        "response": response,
+        "command": utils::redact_args(&args, &secrets),
+        "cli_version": env!("CARGO_PKG_VERSION"),
+        "time": chrono::Utc::now().to_rfc3339(),
+    });
+    let contents = serde_json::to_string_pretty(&dump).unwrap_or_default();
+    match utils::save_to_file(path, contents.as_bytes()) {
+        Ok(()) => eprintln!("{} {}", "API response saved to".dimmed(), path.display()),
+        Err(err) => eprintln!("{} Couldn't write --dump-raw-response: {}", "Warning:".yellow(), err),
+    }
+}
+
+/// Resolve the settings every command shares; flags win over the config file
+fn build_context(cli: &Cli, config: &Config, api_key: Option<String>, api_base: Option<String>) -> Context {
+    Context {
+        api_key,
+        api_base,
+        json: cli.json,
+        verbose: cli.verbose,
+        output_template: cli.output_template.clone().or(config.defaults.output_template.clone()),
+        poll_interval: config.defaults.poll_interval.clone(),
+        retries: cli.retries.or(config.defaults.retries),
+        block_selectors: config.defaults.block_selectors.clone(),
     }
 }
 
@@ -154,9 +299,41 @@ async fn run(cli: Cli) -> CliResult<()> {
     // Get API key with priority: CLI > env > config
     let api_key = cli
         .api_key
+        .clone()
         .or_else(|| std::env::var("ALLSCREENSHOTS_API_KEY").ok())
         .or_else(|| config.auth.api_key.clone());
 
+    // Get API base with priority: CLI > env > config
+    let api_base = cli
+        .api_base
+        .clone()
+        .or_else(|| config.auth.api_base.clone())
+        .map(|base| utils::normalize_api_base(&base))
+        .transpose()?;
+
+    if let Some(ref mode) = cli.chmod {
+        let mode = utils::parse_mode(mode)?;
+        if cfg!(unix) {
+            utils::set_output_mode(mode);
+        } else {
+            eprintln!("{} --chmod has no effect on this platform", "Warning:".yellow());
+        }
+    }
+
+    utils::set_no_create_dirs(cli.no_create_dirs);
+    utils::set_strict_url(cli.strict_url || config.defaults.strict_url.unwrap_or(false));
+
+    // Cap terminal previews: CLI > config
+    display::set_max_width(cli.display_max_width.or(config.display.max_width));
+
+    if cli.verbose {
+        if let Some(ref base) = api_base {
+            eprintln!("{} {}", "API base:".dimmed(), base);
+        }
+    }
+
+    let ctx = build_context(&cli, &config, api_key, api_base);
+
     // Handle quick capture (allscreenshots <URL>)
     if let Some(ref url) = cli.url {
         let should_display = if cli.no_display {
@@ -167,7 +344,7 @@ async fn run(cli: Cli) -> CliResult<()> {
 
         return commands::capture::quick_capture(
             url,
-            api_key.as_deref(),
+            &ctx,
             cli.output.as_ref().map(|p| p.to_str().unwrap()),
             cli.device.as_deref(),
             cli.full_page,
@@ -179,34 +356,43 @@ async fn run(cli: Cli) -> CliResult<()> {
     // Handle subcommands
     match cli.command {
         Some(Commands::Capture(args)) => {
-            commands::capture::execute(args, api_key).await
+            commands::capture::execute(*args, &ctx).await
         }
         Some(Commands::Async(args)) => {
-            commands::async_capture::execute(args, api_key).await
+            commands::async_capture::execute(args, &ctx).await
         }
         Some(Commands::Batch(args)) => {
-            commands::batch::execute(args, api_key).await
+            commands::batch::execute(args, &ctx).await
         }
         Some(Commands::Compose(args)) => {
-            commands::compose::execute(args, api_key).await
+            commands::compose::execute(args, &ctx).await
+        }
+        Some(Commands::Diff(args)) => {
+            commands::diff::execute(args)
+        }
+        Some(Commands::Pdf(args)) => {
+            commands::pdf::execute(args, &ctx).await
         }
         Some(Commands::Schedule(cmd)) => {
-            commands::schedule::execute(cmd, api_key).await
+            commands::schedule::execute(cmd, &ctx).await
         }
         Some(Commands::Usage(args)) => {
-            commands::usage::execute(args, api_key).await
+            commands::usage::execute(args, &ctx).await
+        }
+        Some(Commands::Quota(args)) => {
+            commands::usage::quota(args, &ctx).await
         }
         Some(Commands::Config(cmd)) => {
             commands::config::execute(cmd).await
         }
         Some(Commands::Jobs(cmd)) => {
-            commands::jobs::execute(cmd, api_key).await
+            commands::jobs::execute(cmd, &ctx).await
         }
         Some(Commands::Gallery(args)) => {
-            commands::gallery::execute(args, api_key).await
+            commands::gallery::execute(args, &ctx).await
         }
         Some(Commands::Watch(args)) => {
-            commands::watch::execute(args, api_key).await
+            commands::watch::execute(args, &ctx).await
         }
         Some(Commands::Completions { shell, instructions }) => {
             let shell = commands::completions::parse_shell(&shell)?;
@@ -220,9 +406,29 @@ async fn run(cli: Cli) -> CliResult<()> {
             }
         }
         Some(Commands::Devices) => {
-            print_devices();
+            print_devices(ctx.json);
+            Ok(())
+        }
+        Some(Commands::Formats) => {
+            print_formats(ctx.json);
             Ok(())
         }
+        Some(Commands::Metadata(args)) => {
+            commands::metadata::execute(args, &ctx).await
+        }
+        Some(Commands::Doctor) => {
+            commands::doctor::execute(&ctx).await
+        }
+        Some(Commands::Env(args)) => {
+            commands::env::execute(args, &ctx)
+        }
+        Some(Commands::Version) => {
+            commands::version::execute(&ctx)
+        }
+        #[cfg(feature = "self-update")]
+        Some(Commands::SelfUpdate(args)) => {
+            commands::update::execute(args).await
+        }
         None => {
             // No URL and no subcommand - show help
             print_welcome();
@@ -233,36 +439,11 @@ async fn run(cli: Cli) -> CliResult<()> {
 
 fn print_welcome() {
     println!();
-    println!(
-        "{}",
-        "  █████╗ ██╗     ██╗     ███████╗ ██████╗██████╗ ███████╗███████╗███╗   ██╗███████╗██╗  ██╗ ██████╗ ████████╗███████╗"
-            .cyan()
-    );
-    println!(
-        "{}",
-        " ██╔══██╗██║     ██║     ██╔════╝██╔════╝██╔══██╗██╔════╝██╔════╝████╗  ██║██╔════╝██║  ██║██╔═══██╗╚══██╔══╝██╔════╝"
-            .cyan()
-    );
-    println!(
-        "{}",
-        " ███████║██║     ██║     ███████╗██║     ██████╔╝█████╗  █████╗  ██╔██╗ ██║███████╗███████║██║   ██║   ██║   ███████╗"
-            .cyan()
-    );
-    println!(
-        "{}",
-        " ██╔══██║██║     ██║     ╚════██║██║     ██╔══██╗██╔══╝  ██╔══╝  ██║╚██╗██║╚════██║██╔══██║██║   ██║   ██║   ╚════██║"
-            .cyan()
-    );
-    println!(
-        "{}",
-        " ██║  ██║███████╗███████╗███████║╚██████╗██║  ██║███████╗███████╗██║ ╚████║███████║██║  ██║╚██████╔╝   ██║   ███████║"
-            .cyan()
-    );
-    println!(
-        "{}",
-        " ╚═╝  ╚═╝╚══════╝╚══════╝╚══════╝ ╚═════╝╚═╝  ╚═╝╚══════╝╚══════╝╚═╝  ╚═══╝╚══════╝╚═╝  ╚═╝ ╚═════╝    ╚═╝   ╚══════╝"
-            .cyan()
-    );
+    if display::icons::is_plain() {
+        println!("  {}", "ALLSCREENSHOTS".cyan().bold());
+    } else {
+        print_banner();
+    }
     println!();
     println!(
         "  {}",
@@ -279,7 +460,7 @@ fn print_welcome() {
         println!();
         println!(
             "  1. Get your API key at: {}",
-            "https://dashboard.allscreenshots.com/api-keys".cyan().underline()
+            link_url("https://dashboard.allscreenshots.com/api-keys")
         );
         println!(
             "  2. Set it up: {}",
@@ -313,31 +494,110 @@ fn print_welcome() {
     println!();
 }
 
-fn print_devices() {
-    println!("{}", "Available Device Presets".bold().underline());
-    println!();
+fn print_banner() {
+    println!(
+        "{}",
+        "  █████╗ ██╗     ██╗     ███████╗ ██████╗██████╗ ███████╗███████╗███╗   ██╗███████╗██╗  ██╗ ██████╗ ████████╗███████╗"
+            .cyan()
+    );
+    println!(
+        "{}",
+        " ██╔══██╗██║     ██║     ██╔════╝██╔════╝██╔══██╗██╔════╝██╔════╝████╗  ██║██╔════╝██║  ██║██╔═══██╗╚══██╔══╝██╔════╝"
+            .cyan()
+    );
+    println!(
+        "{}",
+        " ███████║██║     ██║     ███████╗██║     ██████╔╝█████╗  █████╗  ██╔██╗ ██║███████╗███████║██║   ██║   ██║   ███████╗"
+            .cyan()
+    );
+    println!(
+        "{}",
+        " ██╔══██║██║     ██║     ╚════██║██║     ██╔══██╗██╔══╝  ██╔══╝  ██║╚██╗██║╚════██║██╔══██║██║   ██║   ██║   ╚════██║"
+            .cyan()
+    );
+    println!(
+        "{}",
+        " ██║  ██║███████╗███████╗███████║╚██████╗██║  ██║███████╗███████╗██║ ╚████║███████║██║  ██║╚██████╔╝   ██║   ███████║"
+            .cyan()
+    );
+    println!(
+        "{}",
+        " ╚═╝  ╚═╝╚══════╝╚══════╝╚══════╝ ╚═════╝╚═╝  ╚═╝╚══════╝╚══════╝╚═╝  ╚═══╝╚══════╝╚═╝  ╚═╝ ╚═════╝    ╚═╝   ╚══════╝"
+            .cyan()
+    );
+}
 
-    println!("{}", "Desktop".cyan().bold());
-    for (name, resolution) in utils::device_presets().iter().filter(|(n, _)| {
-        n.starts_with("Desktop") || n.starts_with("Laptop")
-    }) {
-        println!("  {:<25} {}", name, resolution.dimmed());
+fn print_formats(json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(utils::FORMATS).unwrap_or_default());
+        return;
     }
 
-    println!();
-    println!("{}", "Tablet".cyan().bold());
-    for (name, resolution) in utils::device_presets().iter().filter(|(n, _)| {
-        n.starts_with("Tablet") || n.starts_with("iPad")
-    }) {
-        println!("  {:<25} {}", name, resolution.dimmed());
+    let yes_no = |supported: bool| if supported { "yes".green() } else { "no".dimmed() };
+    let mut table = display::Table::new(&["Format", "Quality", "Full page", "Metadata", "Preview", "Notes"]);
+    for info in utils::FORMATS {
+        let name = match info.aliases {
+            [] => info.name.to_string(),
+            aliases => format!("{} ({})", info.name, aliases.join(", ")),
+        };
+        table.add_row(vec![
+            name,
+            yes_no(info.quality).to_string(),
+            yes_no(info.full_page).to_string(),
+            yes_no(info.metadata).to_string(),
+            yes_no(info.display).to_string(),
+            info.note.to_string(),
+        ]);
     }
+    table.print();
 
     println!();
-    println!("{}", "Mobile".cyan().bold());
-    for (name, resolution) in utils::device_presets().iter().filter(|(n, _)| {
-        n.starts_with("iPhone") || n.starts_with("Android")
-    }) {
-        println!("  {:<25} {}", name, resolution.dimmed());
+    println!(
+        "{}",
+        "Use with: allscreenshots capture <url> --format webp --quality 80".dimmed()
+    );
+    println!(
+        "{}",
+        "--format auto lets the server choose; the file is named after what comes back".dimmed()
+    );
+}
+
+fn print_devices(json: bool) {
+    let presets = utils::device_presets();
+
+    if json {
+        #[derive(serde::Serialize)]
+        struct Device {
+            name: &'static str,
+            width: Option<u32>,
+            height: Option<u32>,
+            category: &'static str,
+        }
+
+        let devices: Vec<Device> = presets
+            .iter()
+            .map(|&(name, resolution)| {
+                let (width, height) = utils::parse_resolution(resolution).unzip();
+                Device {
+                    name,
+                    width,
+                    height,
+                    category: utils::device_category(name),
+                }
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&devices).unwrap_or_default());
+        return;
+    }
+
+    println!("{}", "Available Device Presets".bold().underline());
+
+    for (category, title) in [("desktop", "Desktop"), ("tablet", "Tablet"), ("mobile", "Mobile")] {
+        println!();
+        println!("{}", title.cyan().bold());
+        for (name, resolution) in presets.iter().filter(|(n, _)| utils::device_category(n) == category) {
+            println!("  {:<25} {}", name, resolution.dimmed());
+        }
     }
 
     println!();
@@ -345,4 +605,27 @@ fn print_devices() {
         "{}",
         "Use with: allscreenshots <url> --device \"Device Name\"".dimmed()
     );
+    println!(
+        "{}",
+        "Shorthands: mobile (iPhone 14), desktop (Desktop HD), tablet (iPad)".dimmed()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retries_flag_over_config() {
+        let mut config = Config::default();
+        config.defaults.retries = Some(5);
+
+        let from_config = Cli::parse_from(["allscreenshots", "devices"]);
+        assert_eq!(build_context(&from_config, &config, None, None).retries, Some(5));
+
+        let disabled = Cli::parse_from(["allscreenshots", "--retries", "0", "devices"]);
+        let ctx = build_context(&disabled, &config, None, None);
+        assert_eq!(ctx.retries, Some(0));
+        assert_eq!(ctx.retry_policy().max_attempts, 1);
+    }
 }
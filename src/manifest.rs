@@ -0,0 +1,130 @@
+use crate::error::{CliError, CliResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Status recorded for URLs that rendered and (unless --no-save) were saved
+pub const COMPLETED: &str = "COMPLETED";
+
+/// Per-URL results of a batch run, written with --manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchManifest {
+    /// Bulk job that produced the latest results
+    pub job_id: String,
+    pub output_dir: PathBuf,
+    pub format: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Position in the original batch, used for the file name prefix
+    pub index: usize,
+    pub url: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Whether the capture differed from the previous file (--only-save-changed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changed: Option<bool>,
+}
+
+impl BatchManifest {
+    /// Read a manifest written by a previous run
+    pub fn load(path: &Path) -> CliResult<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| CliError::FileReadError(format!("{}: {}", path.display(), e)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| CliError::Other(format!("Invalid manifest {}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, path: &Path) -> CliResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
+        fs::write(path, json)
+            .map_err(|e| CliError::FileWriteError(format!("{}: {}", path.display(), e)))
+    }
+
+    /// URLs that didn't complete, in their original order
+    pub fn failed_urls(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|e| e.status != COMPLETED)
+            .map(|e| e.url.clone())
+            .collect()
+    }
+
+    /// Original batch position of a URL
+    pub fn index_of(&self, url: &str) -> Option<usize> {
+        self.entries.iter().find(|e| e.url == url).map(|e| e.index)
+    }
+
+    /// Replace entries for the same URLs with newer results
+    pub fn merge(&mut self, job_id: &str, entries: Vec<ManifestEntry>) {
+        self.job_id = job_id.to_string();
+        for entry in entries {
+            match self.entries.iter_mut().find(|e| e.url == entry.url) {
+                Some(existing) => *existing = entry,
+                None => self.entries.push(entry),
+            }
+        }
+        self.entries.sort_by_key(|e| e.index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(index: usize, url: &str, status: &str) -> ManifestEntry {
+        ManifestEntry {
+            index,
+            url: url.to_string(),
+            status: status.to_string(),
+            path: None,
+            error: None,
+            changed: None,
+        }
+    }
+
+    fn manifest() -> BatchManifest {
+        BatchManifest {
+            job_id: "job_1".to_string(),
+            output_dir: PathBuf::from("./screenshots"),
+            format: "png".to_string(),
+            entries: vec![
+                entry(0, "https://a.com", COMPLETED),
+                entry(1, "https://b.com", "FAILED"),
+                entry(2, "https://c.com", "FAILED"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_failed_urls() {
+        assert_eq!(manifest().failed_urls(), vec!["https://b.com", "https://c.com"]);
+        assert_eq!(manifest().index_of("https://c.com"), Some(2));
+        assert_eq!(manifest().index_of("https://d.com"), None);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut manifest = manifest();
+        manifest.merge("job_2", vec![entry(1, "https://b.com", COMPLETED)]);
+
+        assert_eq!(manifest.job_id, "job_2");
+        assert_eq!(manifest.entries.len(), 3);
+        assert_eq!(manifest.failed_urls(), vec!["https://c.com"]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("manifest.json");
+
+        manifest().save(&path).unwrap();
+        assert_eq!(BatchManifest::load(&path).unwrap(), manifest());
+    }
+}
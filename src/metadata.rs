@@ -0,0 +1,306 @@
+use crate::error::{CliError, CliResult};
+use allscreenshots_sdk::ScreenshotRequest;
+use chrono::Local;
+use exif::experimental::Writer;
+use exif::{Field, In, Tag, Value};
+use img_parts::png::{Png, PngChunk};
+use img_parts::{Bytes, DynImage, ImageEXIF};
+use serde::Serialize;
+use std::io::Cursor;
+use std::path::Path;
+
+const PNG_TEXT_CHUNK: [u8; 4] = *b"tEXt";
+
+const KEY_URL: &str = "URL";
+const KEY_CAPTURED_AT: &str = "Creation Time";
+const KEY_DEVICE: &str = "Device";
+const KEY_SOFTWARE: &str = "Software";
+
+/// Capture details embedded into saved screenshots
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ScreenshotMetadata {
+    pub url: Option<String>,
+    pub captured_at: Option<String>,
+    pub device: Option<String>,
+    pub software: Option<String>,
+}
+
+impl ScreenshotMetadata {
+    /// Describe a capture of `url` taken now by this CLI
+    pub fn new(url: &str, device: Option<&str>) -> Self {
+        Self {
+            url: Some(url.to_string()),
+            captured_at: Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+            device: device.map(String::from),
+            software: Some(format!("allscreenshots-cli {}", env!("CARGO_PKG_VERSION"))),
+        }
+    }
+
+    fn entries(&self) -> Vec<(&'static str, &str)> {
+        [
+            (KEY_URL, &self.url),
+            (KEY_CAPTURED_AT, &self.captured_at),
+            (KEY_DEVICE, &self.device),
+            (KEY_SOFTWARE, &self.software),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_deref().map(|v| (key, v)))
+        .collect()
+    }
+
+    fn set(&mut self, key: &str, value: String) {
+        match key {
+            KEY_URL => self.url = Some(value),
+            KEY_CAPTURED_AT => self.captured_at = Some(value),
+            KEY_DEVICE => self.device = Some(value),
+            KEY_SOFTWARE => self.software = Some(value),
+            _ => {}
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+}
+
+/// JSON sidecar written next to a screenshot with `capture --meta`
+#[derive(Debug, Serialize)]
+pub struct Sidecar<'a> {
+    #[serde(flatten)]
+    pub metadata: ScreenshotMetadata,
+    pub file: Option<&'a Path>,
+    pub format: &'a str,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// File size in bytes
+    pub size: u64,
+    /// The request sent to the API
+    pub options: &'a ScreenshotRequest,
+}
+
+impl Sidecar<'_> {
+    pub fn save(&self, path: &Path) -> CliResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| CliError::Other(format!("Failed to serialize: {}", e)))?;
+        crate::utils::save_to_file(path, json.as_bytes())
+    }
+}
+
+/// Embed metadata into image bytes without re-encoding the image.
+///
+/// PNGs get `tEXt` chunks, JPEG and WebP get an EXIF block.
+/// Returns `None` for formats that can't carry metadata (e.g. PDF).
+pub fn embed_metadata(image_bytes: &[u8], meta: &ScreenshotMetadata) -> CliResult<Option<Vec<u8>>> {
+    let image = match parse_image(image_bytes)? {
+        Some(image) => image,
+        None => return Ok(None),
+    };
+
+    let image = match image {
+        DynImage::Png(mut png) => {
+            for (key, value) in meta.entries() {
+                remove_text_chunk(&mut png, key);
+                let mut contents = Vec::with_capacity(key.len() + 1 + value.len());
+                contents.extend_from_slice(key.as_bytes());
+                contents.push(0);
+                contents.extend_from_slice(value.as_bytes());
+                // tEXt chunks go right after IHDR
+                png.chunks_mut()
+                    .insert(1, PngChunk::new(PNG_TEXT_CHUNK, Bytes::from(contents)));
+            }
+            DynImage::Png(png)
+        }
+        mut other => {
+            other.set_exif(Some(build_exif(meta)?.into()));
+            other
+        }
+    };
+
+    Ok(Some(image.encoder().bytes().to_vec()))
+}
+
+/// Read metadata back from image bytes.
+///
+/// Returns `None` if the format can't carry metadata, and an empty
+/// `ScreenshotMetadata` if the image has none.
+pub fn read_metadata(image_bytes: &[u8]) -> CliResult<Option<ScreenshotMetadata>> {
+    let image = match parse_image(image_bytes)? {
+        Some(image) => image,
+        None => return Ok(None),
+    };
+
+    let mut meta = ScreenshotMetadata::default();
+
+    if let DynImage::Png(ref png) = image {
+        for chunk in png.chunks_by_type(PNG_TEXT_CHUNK) {
+            let contents = chunk.contents();
+            if let Some(nul) = contents.iter().position(|&b| b == 0) {
+                let key = String::from_utf8_lossy(&contents[..nul]);
+                let value = String::from_utf8_lossy(&contents[nul + 1..]).to_string();
+                meta.set(&key, value);
+            }
+        }
+    }
+
+    // Fall back to EXIF (JPEG/WebP, or PNGs written by other tools)
+    if meta.is_empty() {
+        if let Some(raw) = image.exif() {
+            let exif = exif::Reader::new()
+                .read_raw(raw.to_vec())
+                .map_err(|e| CliError::Other(format!("Failed to parse EXIF data: {}", e)))?;
+
+            for (tag, key) in exif_tags() {
+                if let Some(field) = exif.get_field(tag, In::PRIMARY) {
+                    if let Value::Ascii(ref values) = field.value {
+                        if let Some(value) = values.first() {
+                            let mut value = String::from_utf8_lossy(value).to_string();
+                            if tag == Tag::DateTime {
+                                value = value.replacen(':', "-", 2);
+                            }
+                            meta.set(key, value);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Some(meta))
+}
+
+fn parse_image(image_bytes: &[u8]) -> CliResult<Option<DynImage>> {
+    DynImage::from_bytes(Bytes::copy_from_slice(image_bytes))
+        .map_err(|e| CliError::Other(format!("Failed to parse image: {}", e)))
+}
+
+fn remove_text_chunk(png: &mut Png, key: &str) {
+    png.chunks_mut().retain(|chunk| {
+        chunk.kind() != PNG_TEXT_CHUNK
+            || !chunk.contents().starts_with(format!("{}\0", key).as_bytes())
+    });
+}
+
+/// EXIF tags used to store each metadata field
+fn exif_tags() -> [(Tag, &'static str); 4] {
+    [
+        (Tag::ImageDescription, KEY_URL),
+        (Tag::DateTime, KEY_CAPTURED_AT),
+        (Tag::Model, KEY_DEVICE),
+        (Tag::Software, KEY_SOFTWARE),
+    ]
+}
+
+fn build_exif(meta: &ScreenshotMetadata) -> CliResult<Vec<u8>> {
+    let entries = meta.entries();
+    let fields: Vec<Field> = exif_tags()
+        .into_iter()
+        .filter_map(|(tag, key)| {
+            let (_, value) = entries.iter().find(|(k, _)| *k == key)?;
+            // EXIF dates use colons: "YYYY:MM:DD HH:MM:SS"
+            let value = if tag == Tag::DateTime {
+                value.replacen('-', ":", 2)
+            } else {
+                value.to_string()
+            };
+            Some(Field {
+                tag,
+                ifd_num: In::PRIMARY,
+                value: Value::Ascii(vec![value.into_bytes()]),
+            })
+        })
+        .collect();
+
+    let mut writer = Writer::new();
+    for field in &fields {
+        writer.push_field(field);
+    }
+
+    let mut buf = Cursor::new(Vec::new());
+    writer
+        .write(&mut buf, false)
+        .map_err(|e| CliError::Other(format!("Failed to build EXIF data: {}", e)))?;
+
+    Ok(buf.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbImage};
+
+    fn sample_image(format: ImageFormat) -> Vec<u8> {
+        let img = RgbImage::from_pixel(4, 4, image::Rgb([255, 255, 255]));
+        let mut buf = Cursor::new(Vec::new());
+        img.write_to(&mut buf, format).unwrap();
+        buf.into_inner()
+    }
+
+    fn sample_metadata() -> ScreenshotMetadata {
+        ScreenshotMetadata {
+            url: Some("https://example.com".to_string()),
+            captured_at: Some("2024-01-02 03:04:05".to_string()),
+            device: Some("iPhone 14".to_string()),
+            software: Some("allscreenshots-cli 0.1.0".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_png_metadata_roundtrip() {
+        let meta = sample_metadata();
+        let bytes = embed_metadata(&sample_image(ImageFormat::Png), &meta)
+            .unwrap()
+            .unwrap();
+        assert!(image::load_from_memory(&bytes).is_ok());
+        assert_eq!(read_metadata(&bytes).unwrap(), Some(meta));
+    }
+
+    #[test]
+    fn test_jpeg_metadata_roundtrip() {
+        let meta = sample_metadata();
+        let bytes = embed_metadata(&sample_image(ImageFormat::Jpeg), &meta)
+            .unwrap()
+            .unwrap();
+        assert!(image::load_from_memory(&bytes).is_ok());
+        assert_eq!(read_metadata(&bytes).unwrap(), Some(meta));
+    }
+
+    #[test]
+    fn test_reencode_strips_metadata() {
+        let bytes = embed_metadata(&sample_image(ImageFormat::Png), &sample_metadata())
+            .unwrap()
+            .unwrap();
+        let stripped = crate::utils::reencode_image(&bytes, ImageFormat::Png, None).unwrap();
+        assert_eq!(read_metadata(&stripped).unwrap(), Some(ScreenshotMetadata::default()));
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        let pdf = b"%PDF-1.4\n%%EOF";
+        assert_eq!(embed_metadata(pdf, &sample_metadata()).unwrap(), None);
+        assert_eq!(read_metadata(pdf).unwrap(), None);
+    }
+
+    #[test]
+    fn test_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shot.json");
+        let request = ScreenshotRequest::builder().url("https://example.com").build().unwrap();
+        Sidecar {
+            metadata: sample_metadata(),
+            file: Some(Path::new("shot.png")),
+            format: "png",
+            width: Some(4),
+            height: Some(4),
+            size: 120,
+            options: &request,
+        }
+        .save(&path)
+        .unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(json["url"], "https://example.com");
+        assert_eq!(json["device"], "iPhone 14");
+        assert_eq!(json["width"], 4);
+        assert_eq!(json["options"]["url"], "https://example.com");
+    }
+}
@@ -0,0 +1,163 @@
+use crate::error::{CliError, CliResult};
+use lopdf::{dictionary, Document, Object, ObjectId};
+
+/// Page attributes a page may inherit from its parent page tree nodes
+const INHERITABLE: [&[u8]; 4] = [b"MediaBox", b"CropBox", b"Resources", b"Rotate"];
+
+/// Whether the bytes look like a PDF document
+pub fn is_pdf(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"%PDF-")
+}
+
+/// Merge PDF documents into one, keeping document and page order
+pub fn merge_pdfs(inputs: &[Vec<u8>]) -> CliResult<Vec<u8>> {
+    let mut merged = Document::with_version("1.5");
+    let pages_id = merged.new_object_id();
+    let mut next_id = merged.max_id + 1;
+    let mut kids = Vec::new();
+
+    for (i, bytes) in inputs.iter().enumerate() {
+        let mut doc = Document::load_mem(bytes)
+            .map_err(|e| CliError::Other(format!("Failed to read PDF {}: {}", i + 1, e)))?;
+        doc.renumber_objects_with(next_id);
+        next_id = doc.max_id + 1;
+
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+
+        // The source page trees are dropped, so pages keep what they inherited
+        for &page_id in &pages {
+            let inherited = inherited_attributes(&doc, page_id);
+            if let Ok(page) = doc.get_dictionary_mut(page_id) {
+                for (key, value) in inherited {
+                    if !page.has(key) {
+                        page.set(key, value);
+                    }
+                }
+                page.set("Parent", pages_id);
+            }
+        }
+
+        for (id, object) in doc.objects {
+            match object.type_name().unwrap_or(b"") {
+                b"Catalog" | b"Pages" | b"Outlines" | b"Outline" => {}
+                _ => {
+                    merged.objects.insert(id, object);
+                }
+            }
+        }
+
+        kids.extend(pages.into_iter().map(Object::Reference));
+    }
+
+    let count = kids.len() as i64;
+    merged.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+        }),
+    );
+
+    merged.max_id = next_id;
+    let catalog_id = merged.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    merged.trailer.set("Root", catalog_id);
+    merged.renumber_objects();
+
+    let mut output = Vec::new();
+    merged
+        .save_to(&mut output)
+        .map_err(|e| CliError::Other(format!("Failed to write merged PDF: {}", e)))?;
+    Ok(output)
+}
+
+/// Collect inheritable attributes from a page's ancestors, nearest first
+fn inherited_attributes(doc: &Document, page_id: ObjectId) -> Vec<(&'static [u8], Object)> {
+    let mut found: Vec<(&'static [u8], Object)> = Vec::new();
+    let mut parent = doc
+        .get_dictionary(page_id)
+        .and_then(|page| page.get(b"Parent"))
+        .and_then(Object::as_reference)
+        .ok();
+
+    // Bounded so a malformed, cyclic page tree can't loop forever
+    for _ in 0..32 {
+        let Some(node) = parent.and_then(|id| doc.get_dictionary(id).ok()) else {
+            break;
+        };
+        for key in INHERITABLE {
+            if !found.iter().any(|(k, _)| *k == key) {
+                if let Ok(value) = node.get(key) {
+                    found.push((key, value.clone()));
+                }
+            }
+        }
+        parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A PDF with `pages` blank pages whose MediaBox lives on the page tree
+    fn sample_pdf(pages: usize) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let kids: Vec<Object> = (0..pages)
+            .map(|_| {
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                })
+                .into()
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => pages as i64,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_is_pdf() {
+        assert!(is_pdf(&sample_pdf(1)));
+        assert!(!is_pdf(b"\x89PNG\r\n"));
+    }
+
+    #[test]
+    fn test_merge_pdfs() {
+        let merged = merge_pdfs(&[sample_pdf(2), sample_pdf(1)]).unwrap();
+        let doc = Document::load_mem(&merged).unwrap();
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 3);
+
+        for page_id in pages.values() {
+            assert!(doc.get_dictionary(*page_id).unwrap().has(b"MediaBox"));
+        }
+    }
+
+    #[test]
+    fn test_merge_rejects_invalid_input() {
+        assert!(merge_pdfs(&[b"not a pdf".to_vec()]).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// Token bucket holding one request: requests start at most once per interval,
+/// however many tasks share the limiter
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    /// When the next request may start
+    next: Option<Instant>,
+    /// Requests that had to wait, and for how long in total
+    delayed: u32,
+    waited: Duration,
+}
+
+impl RateLimiter {
+    /// Allow `rate` requests per second (e.g. 0.5 for one every two seconds)
+    pub fn per_second(rate: f64) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / rate),
+            state: Mutex::new(State {
+                next: None,
+                delayed: 0,
+                waited: Duration::ZERO,
+            }),
+        }
+    }
+
+    /// Wait until the next request may start; returns how long that took
+    pub async fn acquire(&self) -> Duration {
+        let delay = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let start = state.next.map_or(now, |next| next.max(now));
+            state.next = Some(start + self.interval);
+
+            let delay = start - now;
+            if !delay.is_zero() {
+                state.delayed += 1;
+                state.waited += delay;
+            }
+            delay
+        };
+
+        if !delay.is_zero() {
+            sleep(delay).await;
+        }
+        delay
+    }
+
+    /// Summary like "Throttled 3 requests (1.5s in total)", or None if nothing waited
+    pub fn report(&self) -> Option<String> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.delayed == 0 {
+            return None;
+        }
+        Some(format!(
+            "Throttled {} request(s) ({:.1}s in total)",
+            state.delayed,
+            state.waited.as_secs_f64()
+        ))
+    }
+}
+
+/// Slowest --rate accepted: one request every 1000 seconds. Far smaller values
+/// would overflow the interval between requests
+const MIN_RATE: f64 = 0.001;
+
+/// Parse a --rate value: requests per second, at least `MIN_RATE`
+pub fn parse_rate(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate >= MIN_RATE => Ok(rate),
+        _ => Err(format!(
+            "'{}' isn't a request rate. Use a number of requests per second from {} up, e.g. 2 or 0.5",
+            s, MIN_RATE
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("2"), Ok(2.0));
+        assert_eq!(parse_rate("0.5"), Ok(0.5));
+        assert!(parse_rate("0").is_err());
+        assert!(parse_rate("-1").is_err());
+        assert_eq!(parse_rate("0.001"), Ok(MIN_RATE));
+        assert!(parse_rate("1e-20").is_err());
+        assert!(parse_rate("fast").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_spaces_requests() {
+        let limiter = RateLimiter::per_second(100.0);
+        let started = Instant::now();
+
+        assert!(limiter.acquire().await.is_zero());
+        limiter.acquire().await;
+        limiter.acquire().await;
+
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert!(limiter.report().unwrap().starts_with("Throttled 2 request(s)"));
+    }
+
+    #[tokio::test]
+    async fn test_no_report_without_waiting() {
+        let limiter = RateLimiter::per_second(1000.0);
+        limiter.acquire().await;
+        assert!(limiter.report().is_none());
+    }
+}
@@ -0,0 +1,282 @@
+use crate::error::{CliError, CliResult};
+use allscreenshots_sdk::AllscreenshotsError;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// How often and how long to retry a failing operation
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_delay: Duration,
+    /// Factor the delay grows by after each retry
+    pub multiplier: f64,
+    /// Which errors are worth retrying
+    pub retry_if: fn(&CliError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            retry_if: is_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (starting at 1)
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.powi(retry.saturating_sub(1) as i32);
+        self.initial_delay.mul_f64(factor).min(self.max_delay)
+    }
+}
+
+/// How often to poll a long-running job for its status
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PollSchedule {
+    /// Always wait the same amount of time
+    Fixed(Duration),
+    /// Poll quickly at first, then back off as the job keeps running
+    Adaptive,
+}
+
+impl PollSchedule {
+    /// Shortest and longest waits for adaptive polling
+    const MIN_DELAY: Duration = Duration::from_millis(500);
+    const MAX_DELAY: Duration = Duration::from_secs(5);
+
+    /// Use a fixed interval if one was given, adaptive polling otherwise
+    pub fn from_interval(interval: Option<&str>) -> CliResult<Self> {
+        match interval {
+            Some(interval) => Ok(PollSchedule::Fixed(crate::utils::parse_duration(interval)?)),
+            None => Ok(PollSchedule::Adaptive),
+        }
+    }
+
+    /// Delay before the next poll, given the time since the job was submitted
+    pub fn next_delay(&self, elapsed: Duration) -> Duration {
+        match self {
+            PollSchedule::Fixed(interval) => *interval,
+            // A fifth of the elapsed time keeps status calls proportional to job length
+            PollSchedule::Adaptive => (elapsed / 5).clamp(Self::MIN_DELAY, Self::MAX_DELAY),
+        }
+    }
+}
+
+/// Longest total wait for an async or batch job unless --poll-timeout says otherwise
+pub const DEFAULT_POLL_TIMEOUT: &str = "30m";
+
+/// Error for a job still running once `timeout` has passed
+pub fn poll_timeout(timeout: Duration, hint: String) -> CliError {
+    CliError::PollTimeout {
+        waited: humantime::format_duration(timeout).to_string(),
+        hint,
+    }
+}
+
+/// Elapsed time and poll count for status lines like "Processing... 00:42, poll 21"
+#[derive(Debug, Clone, Copy)]
+pub struct PollProgress {
+    started: Instant,
+    polls: u32,
+}
+
+impl PollProgress {
+    pub fn new(started: Instant) -> Self {
+        Self { started, polls: 0 }
+    }
+
+    /// Count one status request
+    pub fn record(&mut self) {
+        self.polls += 1;
+    }
+
+    pub fn message(&self, status: &str) -> String {
+        describe_poll(status, self.started.elapsed(), self.polls)
+    }
+}
+
+fn describe_poll(status: &str, elapsed: Duration, polls: u32) -> String {
+    let secs = elapsed.as_secs();
+    let clock = if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    };
+    format!("{}... {}, poll {}", status, clock, polls)
+}
+
+/// Completion percentage a job reports in its metadata (`progress`, 0-100), if any
+pub fn reported_progress(metadata: Option<&serde_json::Value>) -> Option<u64> {
+    let progress = metadata?.get("progress")?.as_f64()?;
+    Some(progress.clamp(0.0, 100.0).round() as u64)
+}
+
+/// Retry transient API errors: rate limits, server errors, timeouts and
+/// connection failures
+pub fn is_retryable(err: &CliError) -> bool {
+    match err {
+        CliError::Sdk(e) => e.is_retryable(),
+        _ => false,
+    }
+}
+
+/// Retry only when the connection couldn't be made, so the request never reached
+/// the API. For calls that aren't safe to repeat, such as creating a job
+pub fn is_connect_failure(err: &CliError) -> bool {
+    matches!(err, CliError::Sdk(AllscreenshotsError::HttpError(e)) if e.is_connect())
+}
+
+/// Run `op` until it succeeds, fails with a non-retryable error, or the
+/// policy runs out of attempts. The last error is returned.
+pub async fn retry_async<T, F, Fut>(mut op: F, policy: &RetryPolicy) -> CliResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = CliResult<T>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < policy.max_attempts && (policy.retry_if)(&e) => {
+                sleep(policy.delay_for(attempt)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use allscreenshots_sdk::AllscreenshotsError;
+    use std::cell::Cell;
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_delay: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_delay_for() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay_for(1), Duration::from_millis(500));
+        assert_eq!(policy.delay_for(2), Duration::from_secs(1));
+        assert_eq!(policy.delay_for(10), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_poll_schedule() {
+        let fixed = PollSchedule::from_interval(Some("2s")).unwrap();
+        assert_eq!(fixed.next_delay(Duration::from_secs(60)), Duration::from_secs(2));
+
+        let adaptive = PollSchedule::from_interval(None).unwrap();
+        assert_eq!(adaptive.next_delay(Duration::ZERO), Duration::from_millis(500));
+        assert_eq!(adaptive.next_delay(Duration::from_secs(10)), Duration::from_secs(2));
+        assert_eq!(adaptive.next_delay(Duration::from_secs(300)), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_describe_poll() {
+        assert_eq!(
+            describe_poll("Waiting", Duration::from_secs(42), 21),
+            "Waiting... 00:42, poll 21"
+        );
+        assert_eq!(
+            describe_poll("Processing", Duration::from_secs(3723), 300),
+            "Processing... 1:02:03, poll 300"
+        );
+    }
+
+    #[test]
+    fn test_reported_progress() {
+        let metadata = serde_json::json!({ "progress": 42.4 });
+        assert_eq!(reported_progress(Some(&metadata)), Some(42));
+        assert_eq!(reported_progress(Some(&serde_json::json!({ "progress": 150 }))), Some(100));
+        assert_eq!(reported_progress(Some(&serde_json::json!({}))), None);
+        assert_eq!(reported_progress(None), None);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&CliError::Sdk(AllscreenshotsError::Timeout)));
+        assert!(!is_retryable(&CliError::NoApiKey));
+    }
+
+    #[test]
+    fn test_is_connect_failure() {
+        // The request may have reached the API, so creating a job again could duplicate it
+        assert!(!is_connect_failure(&CliError::Sdk(AllscreenshotsError::Timeout)));
+        assert!(!is_connect_failure(&CliError::Sdk(AllscreenshotsError::from_api_response(
+            503,
+            None,
+            "Service unavailable"
+        ))));
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let calls = Cell::new(0);
+        let result = retry_async(
+            || {
+                calls.set(calls.get() + 1);
+                async {
+                    if calls.get() < 3 {
+                        Err(CliError::Sdk(AllscreenshotsError::Timeout))
+                    } else {
+                        Ok(calls.get())
+                    }
+                }
+            },
+            &fast_policy(3),
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stops_on_non_retryable_error() {
+        let calls = Cell::new(0);
+        let result: CliResult<()> = retry_async(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err(CliError::Other("bad request".to_string())) }
+            },
+            &fast_policy(5),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let calls = Cell::new(0);
+        let result: CliResult<()> = retry_async(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err(CliError::Sdk(AllscreenshotsError::Timeout)) }
+            },
+            &fast_policy(2),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 2);
+    }
+}
@@ -0,0 +1,78 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+const SHELLS: &[&str] = &["bash", "zsh", "fish", "powershell", "elvish"];
+
+const SUBCOMMANDS: &[&str] = &[
+    "capture",
+    "async",
+    "batch",
+    "compose",
+    "pdf",
+    "schedule",
+    "usage",
+    "quota",
+    "config",
+    "jobs",
+    "gallery",
+    "watch",
+    "completions",
+    "devices",
+    "formats",
+    "metadata",
+    "doctor",
+    "env",
+    "version",
+];
+
+fn completions(shell: &str) -> Command {
+    let mut cmd = cargo_bin_cmd!("allscreenshots");
+    cmd.args(["completions", shell]);
+    cmd
+}
+
+#[test]
+fn generates_completions_for_every_shell() {
+    for shell in SHELLS {
+        let output = completions(shell).output().unwrap();
+        assert!(output.status.success(), "{} completions failed", shell);
+
+        let script = String::from_utf8(output.stdout).unwrap();
+        assert!(script.contains("allscreenshots"), "{} script lacks binary name", shell);
+        for subcommand in SUBCOMMANDS {
+            assert!(
+                script.contains(subcommand),
+                "{} script lacks subcommand '{}'",
+                shell,
+                subcommand
+            );
+        }
+    }
+}
+
+#[test]
+fn accepts_shell_aliases_and_any_case() {
+    for shell in ["BASH", "Zsh", "ps"] {
+        completions(shell).assert().success().stdout(predicate::str::is_empty().not());
+    }
+}
+
+#[test]
+fn rejects_unknown_shell() {
+    completions("tcsh")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown shell 'tcsh'"));
+}
+
+#[test]
+fn prints_instructions_for_every_shell() {
+    for shell in SHELLS {
+        completions(shell)
+            .arg("--instructions")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!("allscreenshots completions {}", shell)));
+    }
+}
@@ -0,0 +1,181 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use assert_cmd::Command;
+
+/// A command with no API key from the environment or a config file
+fn without_api_key(home: &tempfile::TempDir) -> Command {
+    let mut cmd = cargo_bin_cmd!("allscreenshots");
+    cmd.env_remove("ALLSCREENSHOTS_API_KEY")
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join(".config"));
+    cmd
+}
+
+#[test]
+fn json_errors_go_to_stderr() {
+    let home = tempfile::tempdir().unwrap();
+    let output = without_api_key(&home)
+        .args(["--json", "capture", "example.com"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(json["error"]["code"], "no_api_key");
+    assert_eq!(json["error"]["message"], "No API key found");
+    assert_eq!(json["error"]["exit_code"], 3);
+}
+
+#[test]
+fn friendly_errors_without_json() {
+    let home = tempfile::tempdir().unwrap();
+    let output = without_api_key(&home)
+        .args(["capture", "example.com"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No API key found!"));
+    assert!(serde_json::from_str::<serde_json::Value>(stderr.trim()).is_err());
+}
+
+#[test]
+fn dump_raw_response_only_on_api_errors() {
+    let home = tempfile::tempdir().unwrap();
+    let dump = home.path().join("response.json");
+    let output = without_api_key(&home)
+        .args(["capture", "example.com", "--dump-raw-response"])
+        .arg(&dump)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(!dump.exists());
+}
+
+#[test]
+fn dry_run_sends_nothing_in_multi_capture_modes() {
+    let home = tempfile::tempdir().unwrap();
+    // Flags for each mode and how many distinct requests it prints
+    let modes: &[(&[&str], usize)] = &[
+        (&["--selectors", ".header,.footer"], 2),
+        (&["--devices", "mobile,desktop", "--parallel"], 2),
+        (&["--compare-against", "staging.example.com"], 2),
+        (&["--both-viewport-and-full"], 2),
+        // The same request every time
+        (&["--repeat", "3"], 1),
+    ];
+
+    // Without an API key any real capture would fail, so success means nothing was sent
+    for (mode, requests) in modes {
+        let output = without_api_key(&home)
+            .args(["capture", "example.com", "--print-request", "--dry-run"])
+            .args(*mode)
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{:?}: {}", mode, String::from_utf8_lossy(&output.stderr));
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let printed = json.as_array().map_or(1, Vec::len);
+        assert_eq!(printed, *requests, "{:?}", mode);
+    }
+}
+
+#[test]
+fn config_set_rejects_unknown_format() {
+    let home = tempfile::tempdir().unwrap();
+    let output = without_api_key(&home)
+        .args(["config", "set", "defaults.format", "jpg "])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown format"));
+
+    without_api_key(&home)
+        .args(["config", "set", "defaults.format", "jpg ", "--force"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn config_set_poll_defaults() {
+    let home = tempfile::tempdir().unwrap();
+    let output = without_api_key(&home)
+        .args(["config", "set", "defaults.poll_interval", "soon"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid poll interval"));
+
+    for (key, value) in [("defaults.poll_interval", "1s"), ("defaults.retries", "3")] {
+        without_api_key(&home).args(["config", "set", key, value]).assert().success();
+        let output = without_api_key(&home).args(["config", "get", key]).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), value);
+    }
+}
+
+#[test]
+fn config_set_manages_block_selectors() {
+    let home = tempfile::tempdir().unwrap();
+    let set = |args: &[&str]| {
+        without_api_key(&home)
+            .args(["config", "set", "defaults.block_selectors"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let get = || {
+        let output = without_api_key(&home)
+            .args(["config", "get", "defaults.block_selectors"])
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    assert!(set(&["#intercom"]).status.success());
+    assert!(set(&[".promo, .banner", "--append"]).status.success());
+    assert!(set(&["#intercom", "--append"]).status.success());
+    assert_eq!(get(), "#intercom\n.promo, .banner");
+
+    assert!(set(&["#intercom", "--remove"]).status.success());
+    assert_eq!(get(), ".promo, .banner");
+    assert_eq!(set(&["#missing", "--remove"]).status.code(), Some(1));
+
+    let output = without_api_key(&home)
+        .args(["config", "set", "defaults.format", "png", "--append"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("list keys"));
+}
+
+#[test]
+fn no_create_dirs_refuses_missing_directory() {
+    let home = tempfile::tempdir().unwrap();
+    let image = image::RgbImage::from_pixel(4, 4, image::Rgb([255, 0, 0]));
+    let input = home.path().join("in.png");
+    image.save(&input).unwrap();
+    let output = home.path().join("missing/out.png");
+
+    let compose = |extra: &[&str]| {
+        let mut cmd = without_api_key(&home);
+        cmd.args(["compose", "--from-files"])
+            .args([&input, &input])
+            .arg("-o")
+            .arg(&output)
+            .args(extra)
+            .write_stdin("");
+        cmd.output().unwrap()
+    };
+
+    let refused = compose(&["--no-create-dirs", "--no-display"]);
+    assert_eq!(refused.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("--no-create-dirs"));
+    assert!(!output.exists());
+
+    assert!(compose(&["--no-display"]).status.success());
+    assert!(output.exists());
+}